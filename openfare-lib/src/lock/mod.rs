@@ -20,6 +20,10 @@ pub struct Lock {
     pub payees: payee::Payees,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub shares: Option<shares::Shares>,
+
+    /// How applicable plans are selected. The user's configured policy is used if unset.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "plan-selection")]
+    pub plan_selection: Option<plan::selection_policy::SelectionPolicy>,
 }
 
 impl Lock {
@@ -28,6 +32,21 @@ impl Lock {
         let value = serde_json::to_value(&self)?;
         schema::validate(&value)
     }
+
+    /// Selects plans from the given applicable plans using the lock's plan selection policy if
+    /// declared, otherwise the given default policy.
+    pub fn select_plans(
+        &self,
+        applicable_plans: &plan::Plans,
+        default_policy: &plan::selection_policy::SelectionPolicy,
+        parameters: &plan::conditions::Parameters,
+        rate_provider: &dyn crate::price::RateProvider,
+    ) -> Result<plan::Plans> {
+        self.plan_selection
+            .as_ref()
+            .unwrap_or(default_policy)
+            .select(&applicable_plans, &parameters, rate_provider)
+    }
 }
 
 impl std::default::Default for Lock {
@@ -37,6 +56,7 @@ impl std::default::Default for Lock {
             plans: plan::Plans::new(),
             payees: payee::Payees::new(),
             shares: None,
+            plan_selection: None,
         }
     }
 }
@@ -1,3 +1,5 @@
+use anyhow::Result;
+
 pub type Label = String;
 pub type Payees = std::collections::BTreeMap<Label, Payee>;
 pub type PaymentMethodName = String;
@@ -31,3 +33,133 @@ pub fn unique_label(payee_label: &Label, payee: &Payee) -> Label {
         unique_id = unique_id
     )
 }
+
+/// Returns payees and their fractions of a package price.
+///
+/// Payees without shares receive nothing. All payees receive an equal fraction if shares are
+/// undefined.
+pub fn fractions(
+    payees: &Payees,
+    shares: &Option<super::shares::Shares>,
+) -> Vec<(Payee, rust_decimal::Decimal)> {
+    match shares {
+        Some(shares) => {
+            let shares = shares
+                .iter()
+                .filter(|(label, _share)| payees.contains_key(label.as_str()))
+                .collect::<Vec<_>>();
+            let total: u64 = shares.iter().map(|(_, share)| *share).sum();
+            if total == 0 {
+                return vec![];
+            }
+            shares
+                .iter()
+                .filter_map(|(label, share)| {
+                    let payee = payees.get(label.as_str())?;
+                    Some((
+                        payee.clone(),
+                        rust_decimal::Decimal::from(**share) / rust_decimal::Decimal::from(total),
+                    ))
+                })
+                .collect()
+        }
+        None => {
+            if payees.is_empty() {
+                return vec![];
+            }
+            let fraction =
+                rust_decimal::Decimal::from(1) / rust_decimal::Decimal::from(payees.len());
+            payees
+                .iter()
+                .map(|(_label, payee)| (payee.clone(), fraction))
+                .collect()
+        }
+    }
+}
+
+/// Applies payee price caps to package prices.
+///
+/// Package prices are split between payees. Payees are grouped by unique ID across packages.
+/// Where the total of a payee's portions exceeds the payee's cap, the portions are reduced
+/// proportionally so that the total equals the cap. Each package price is given along with the
+/// plan prices from which it is derived and their billing periods. Plan prices are normalized to
+/// the billing period of the cap and compared in the currency of the cap. Where a payee's
+/// packages give differing caps, the first is used.
+///
+/// Returns the capped price of each package.
+pub fn apply_price_caps(
+    package_prices: &Vec<(
+        Payees,
+        Option<super::shares::Shares>,
+        crate::price::Price,
+        Vec<(
+            crate::price::Price,
+            super::plan::billing_period::BillingPeriod,
+        )>,
+    )>,
+    rate_provider: &dyn crate::price::RateProvider,
+) -> Result<Vec<crate::price::Price>> {
+    let zero = rust_decimal::Decimal::from(0);
+
+    let packages_fractions = package_prices
+        .iter()
+        .map(|(payees, shares, _price, _plan_prices)| fractions(&payees, &shares))
+        .collect::<Vec<_>>();
+
+    // Find each payee's cap and total portion of the package prices.
+    let mut caps = std::collections::BTreeMap::<uuid::Uuid, crate::profile::PriceCap>::new();
+    for (payee, _fraction) in packages_fractions.iter().flatten() {
+        if let Some(price_cap) = &payee.profile.price_cap {
+            if !caps.contains_key(&payee.profile.unique_id) {
+                caps.insert(payee.profile.unique_id, price_cap.clone());
+            }
+        }
+    }
+    let mut totals = std::collections::BTreeMap::<uuid::Uuid, rust_decimal::Decimal>::new();
+    for ((_payees, _shares, _price, plan_prices), fractions) in
+        package_prices.iter().zip(&packages_fractions)
+    {
+        for (payee, fraction) in fractions {
+            let cap = match caps.get(&payee.profile.unique_id) {
+                Some(cap) => cap,
+                None => continue,
+            };
+            let cap_billing_period = cap.billing_period.clone().unwrap_or_default();
+            for (plan_price, plan_billing_period) in plan_prices {
+                let plan_price = crate::price::Price {
+                    quantity: plan_billing_period
+                        .normalize(&plan_price.quantity, &cap_billing_period),
+                    currency: plan_price.currency.clone(),
+                };
+                let plan_price = plan_price.to(&cap.price.currency, rate_provider)?;
+                *totals.entry(payee.profile.unique_id).or_insert(zero) +=
+                    plan_price.quantity * fraction;
+            }
+        }
+    }
+
+    // Reduce each package price by the portions which exceed payee caps.
+    Ok(package_prices
+        .iter()
+        .zip(&packages_fractions)
+        .map(|((_payees, _shares, price, _plan_prices), fractions)| {
+            let mut capped_price = price.clone();
+            for (payee, fraction) in fractions {
+                let cap = match caps.get(&payee.profile.unique_id) {
+                    Some(cap) => cap,
+                    None => continue,
+                };
+                let total = totals.get(&payee.profile.unique_id).unwrap_or(&zero);
+                if *total > cap.price.quantity {
+                    let portion = price.quantity * fraction;
+                    capped_price.quantity -= portion - portion * cap.price.quantity / total;
+                }
+            }
+            capped_price.quantity = capped_price.quantity.round_dp_with_strategy(
+                capped_price.currency.decimal_points(),
+                rust_decimal::prelude::RoundingStrategy::AwayFromZero,
+            );
+            capped_price
+        })
+        .collect())
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use chrono::{Datelike, TimeZone};
+
+use strum::IntoEnumIterator;
+
+/// How often a plan price is paid.
+#[derive(
+    Debug,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(into = "String", try_from = "String")]
+pub enum BillingPeriod {
+    OneOff,
+    Monthly,
+    Yearly,
+}
+
+impl std::default::Default for BillingPeriod {
+    fn default() -> Self {
+        Self::OneOff
+    }
+}
+
+impl BillingPeriod {
+    /// Number of months between payments. None if the price is paid once.
+    pub fn months(&self) -> Option<u32> {
+        match self {
+            Self::OneOff => None,
+            Self::Monthly => Some(1),
+            Self::Yearly => Some(12),
+        }
+    }
+
+    /// Converts a price quantity paid at this billing period into the equivalent quantity paid
+    /// at the given billing period. One-off quantities are not converted.
+    pub fn normalize(
+        &self,
+        quantity: &crate::price::Quantity,
+        period: &Self,
+    ) -> crate::price::Quantity {
+        match (self.months(), period.months()) {
+            (Some(from_months), Some(to_months)) => {
+                quantity * crate::price::Quantity::from(to_months)
+                    / crate::price::Quantity::from(from_months)
+            }
+            _ => quantity.clone(),
+        }
+    }
+
+    /// Returns the date at which the next payment falls due given the date of the last
+    /// payment. None if the price is paid once.
+    pub fn next_due(
+        &self,
+        paid: &chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        let months = self.months()?;
+        let month_index = paid.month0() + months;
+        let year = paid.year() + (month_index / 12) as i32;
+        let month = month_index % 12 + 1;
+
+        // Clamp day to the last day of the due month. Example: 31st January -> 28th February
+        let mut day = paid.day();
+        loop {
+            if let Some(date) = chrono::NaiveDate::from_ymd_opt(year, month, day) {
+                return Some(chrono::Utc.from_utc_datetime(&date.and_time(paid.time())));
+            }
+            day -= 1;
+        }
+    }
+}
+
+impl std::string::ToString for BillingPeriod {
+    fn to_string(&self) -> String {
+        match self {
+            Self::OneOff => "one-off",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+        }
+        .to_string()
+    }
+}
+
+impl Into<String> for BillingPeriod {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::convert::TryFrom<&str> for BillingPeriod {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        for period in Self::iter() {
+            if period.to_string().as_str() == value {
+                return Ok(period);
+            }
+        }
+        let error_message = format!(
+            "Error parsing billing period: {}\nAccepted values:\n{}",
+            value,
+            Self::iter()
+                .map(|period| period.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+        Err(anyhow::format_err!(error_message))
+    }
+}
+
+impl std::convert::TryFrom<String> for BillingPeriod {
+    type Error = anyhow::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl std::str::FromStr for BillingPeriod {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value)
+    }
+}
+
+#[test]
+fn test_normalize() {
+    let quantity = crate::price::Quantity::from(10);
+    assert_eq!(
+        BillingPeriod::Monthly.normalize(&quantity, &BillingPeriod::Yearly),
+        crate::price::Quantity::from(120)
+    );
+    assert_eq!(
+        BillingPeriod::Yearly
+            .normalize(&crate::price::Quantity::from(120), &BillingPeriod::Monthly),
+        quantity
+    );
+    assert_eq!(
+        BillingPeriod::OneOff.normalize(&quantity, &BillingPeriod::Yearly),
+        quantity
+    );
+}
+
+#[cfg(test)]
+fn utc_date(year: i32, month: u32, day: u32) -> chrono::DateTime<chrono::Utc> {
+    let date = chrono::NaiveDate::from_ymd_opt(year, month, day).expect("valid date");
+    chrono::Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("valid time"))
+}
+
+#[test]
+fn test_next_due() {
+    let paid = utc_date(2022, 1, 31);
+    assert_eq!(
+        BillingPeriod::Monthly.next_due(&paid),
+        Some(utc_date(2022, 2, 28))
+    );
+    assert_eq!(
+        BillingPeriod::Yearly.next_due(&paid),
+        Some(utc_date(2023, 1, 31))
+    );
+    assert_eq!(BillingPeriod::OneOff.next_due(&paid), None);
+}
@@ -0,0 +1,149 @@
+use super::common;
+use anyhow::Result;
+
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AnnualRevenue(Range);
+
+/// Annual revenue of the organization in USD.
+#[derive(
+    Debug,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(into = "String", try_from = "String")]
+pub enum Range {
+    LessThan1M,
+    GreaterEqual1MTo10M,
+    GreaterEqual10MTo100M,
+    GreaterEqual100MTo1B,
+    GreaterEqual1B,
+}
+
+impl Range {
+    pub fn evaluate(&self, annual_revenue: &Self) -> bool {
+        self == annual_revenue
+    }
+}
+
+impl std::string::ToString for Range {
+    fn to_string(&self) -> String {
+        match self {
+            Self::LessThan1M => "revenue < 1M USD",
+            Self::GreaterEqual1MTo10M => "1M USD <= revenue < 10M USD",
+            Self::GreaterEqual10MTo100M => "10M USD <= revenue < 100M USD",
+            Self::GreaterEqual100MTo1B => "100M USD <= revenue < 1B USD",
+            Self::GreaterEqual1B => "1B USD <= revenue",
+        }
+        .to_string()
+    }
+}
+
+impl Into<String> for Range {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::convert::TryFrom<&str> for Range {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        for range in Self::iter() {
+            if range.to_string().as_str() == value {
+                return Ok(range);
+            }
+        }
+        let error_message = format!(
+            "Error parsing annual revenue range: {}\nAccepted values:\n{}",
+            value,
+            Self::iter()
+                .map(|range| range.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+        Err(anyhow::format_err!(error_message))
+    }
+}
+
+impl std::convert::TryFrom<String> for Range {
+    type Error = anyhow::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl std::convert::TryFrom<&str> for AnnualRevenue {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(Self(Range::try_from(value)?))
+    }
+}
+
+impl common::Condition for AnnualRevenue {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        let annual_revenue = parameters
+            .annual_revenue
+            .as_ref()
+            .ok_or(anyhow::format_err!(
+                "Attempting to evaluate plan conditions using unset parameter `{}`.",
+                self.metadata().name()
+            ))?;
+        Ok(self.0.evaluate(&annual_revenue))
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(AnnualRevenueMetadata) as Box<dyn common::ConditionMetadata>
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AnnualRevenueMetadata;
+
+impl common::ConditionMetadata for AnnualRevenueMetadata {
+    fn name(&self) -> String {
+        "annual-revenue".to_string()
+    }
+
+    fn interactive_set_parameter(
+        &self,
+        parameters: &mut crate::lock::plan::conditions::Parameters,
+    ) -> Result<()> {
+        println!("Select a range for the annual revenue of your organization:");
+        let ranges = Range::iter().collect::<Vec<_>>();
+        let items = ranges.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+        let index = dialoguer::Select::new().items(&items).interact()?;
+
+        if let Some(range) = ranges.get(index) {
+            parameters.annual_revenue = Some(range.clone());
+        }
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, parameters: &crate::lock::plan::conditions::Parameters) -> bool {
+        parameters.annual_revenue.is_some()
+    }
+}
+
+#[test]
+fn test_from_str() -> Result<()> {
+    use common::Condition;
+
+    let range = Range::GreaterEqual10MTo100M;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.annual_revenue = Some(range.clone());
+
+    let annual_revenue = AnnualRevenue::try_from(range.to_string().as_str())?;
+    assert!(annual_revenue.evaluate(&parameters)?);
+
+    parameters.annual_revenue = Some(Range::LessThan1M);
+    assert!(!annual_revenue.evaluate(&parameters)?);
+    Ok(())
+}
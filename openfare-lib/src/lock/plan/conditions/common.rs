@@ -1,4 +1,5 @@
 use anyhow::{format_err, Result};
+use chrono::{TimeZone, Utc};
 
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Operator {
@@ -56,9 +57,113 @@ pub fn evaluate_operator<T: std::cmp::PartialOrd>(
     }
 }
 
+/// Parse a date condition value. Example: "2022-01-31"
+pub fn parse_date(value: &str) -> Result<chrono::DateTime<Utc>> {
+    let date = chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d")?;
+    let time = naive_date_to_utc(&date)?;
+    Ok(time)
+}
+
+pub fn naive_date_to_utc(date: &chrono::NaiveDate) -> Result<chrono::DateTime<Utc>> {
+    // The known 1 hour time offset in seconds
+    let tz_offset = chrono::FixedOffset::east(0);
+    // The known time
+    let time = chrono::NaiveTime::from_hms(0, 0, 0);
+    // Naive date time, with no time zone information
+    let datetime = chrono::NaiveDateTime::new(date.clone(), time);
+
+    let dt_with_tz: chrono::DateTime<chrono::FixedOffset> =
+        tz_offset.from_local_datetime(&datetime).unwrap();
+    let dt_with_tz_utc: chrono::DateTime<Utc> = Utc.from_utc_datetime(&dt_with_tz.naive_utc());
+    Ok(dt_with_tz_utc)
+}
+
+/// Outcome of evaluating plan conditions.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Evaluation {
+    /// Conditions pass.
+    Pass,
+    /// Conditions do not pass.
+    Fail,
+    /// Conditions could not be evaluated. Includes the reason (e.g. an unset parameter).
+    Undecidable(String),
+}
+
+impl Evaluation {
+    pub fn from_result(result: Result<bool>) -> Self {
+        match result {
+            Ok(true) => Self::Pass,
+            Ok(false) => Self::Fail,
+            Err(error) => Self::Undecidable(error.to_string()),
+        }
+    }
+
+    pub fn is_pass(&self) -> bool {
+        *self == Self::Pass
+    }
+
+    /// Passes if all evaluations pass. Fails if any evaluation fails, even if other
+    /// evaluations are undecidable.
+    pub fn all(evaluations: impl Iterator<Item = Self>) -> Self {
+        let mut reasons = vec![];
+        for evaluation in evaluations {
+            match evaluation {
+                Self::Pass => {}
+                Self::Fail => return Self::Fail,
+                Self::Undecidable(reason) => reasons.push(reason),
+            }
+        }
+        if reasons.is_empty() {
+            Self::Pass
+        } else {
+            Self::Undecidable(reasons.join(" "))
+        }
+    }
+
+    /// Passes if any evaluation passes, even if other evaluations are undecidable.
+    pub fn any(evaluations: impl Iterator<Item = Self>) -> Self {
+        let mut reasons = vec![];
+        for evaluation in evaluations {
+            match evaluation {
+                Self::Pass => return Self::Pass,
+                Self::Fail => {}
+                Self::Undecidable(reason) => reasons.push(reason),
+            }
+        }
+        if reasons.is_empty() {
+            Self::Fail
+        } else {
+            Self::Undecidable(reasons.join(" "))
+        }
+    }
+
+    pub fn not(self) -> Self {
+        match self {
+            Self::Pass => Self::Fail,
+            Self::Fail => Self::Pass,
+            Self::Undecidable(reason) => Self::Undecidable(reason),
+        }
+    }
+
+    /// Converts into a boolean. Returns an error if undecidable.
+    pub fn into_result(self) -> Result<bool> {
+        match self {
+            Self::Pass => Ok(true),
+            Self::Fail => Ok(false),
+            Self::Undecidable(reason) => Err(format_err!(reason)),
+        }
+    }
+}
+
 pub trait Condition {
     fn evaluate(&self, parameters: &super::Parameters) -> Result<bool>;
     fn metadata(&self) -> Box<dyn ConditionMetadata>;
+
+    /// Returns metadata for the leaf conditions which require parameters.
+    /// Compound conditions return the metadata of their nested conditions.
+    fn leaf_metadata(&self) -> Vec<Box<dyn ConditionMetadata>> {
+        vec![self.metadata()]
+    }
 }
 
 pub trait ConditionMetadata: std::fmt::Debug {
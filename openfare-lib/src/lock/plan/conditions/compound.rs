@@ -0,0 +1,191 @@
+use super::common;
+use anyhow::Result;
+
+/// Passes if every nested set of conditions passes.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct All(pub Vec<super::Conditions>);
+
+impl common::Condition for All {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        common::Evaluation::all(
+            self.0
+                .iter()
+                .map(|conditions| conditions.evaluate(&parameters)),
+        )
+        .into_result()
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(CompoundMetadata::new("all", &self.0)) as Box<dyn common::ConditionMetadata>
+    }
+
+    fn leaf_metadata(&self) -> Vec<Box<dyn common::ConditionMetadata>> {
+        leaf_metadata(&self.0)
+    }
+}
+
+/// Passes if at least one nested set of conditions passes.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Any(pub Vec<super::Conditions>);
+
+impl common::Condition for Any {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        common::Evaluation::any(
+            self.0
+                .iter()
+                .map(|conditions| conditions.evaluate(&parameters)),
+        )
+        .into_result()
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(CompoundMetadata::new("any", &self.0)) as Box<dyn common::ConditionMetadata>
+    }
+
+    fn leaf_metadata(&self) -> Vec<Box<dyn common::ConditionMetadata>> {
+        leaf_metadata(&self.0)
+    }
+}
+
+/// Passes if the nested set of conditions does not pass.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Not(pub Box<super::Conditions>);
+
+impl common::Condition for Not {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        self.0.evaluate(&parameters).not().into_result()
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(CompoundMetadata::new("not", std::slice::from_ref(&*self.0)))
+            as Box<dyn common::ConditionMetadata>
+    }
+
+    fn leaf_metadata(&self) -> Vec<Box<dyn common::ConditionMetadata>> {
+        self.0.metadata()
+    }
+}
+
+fn leaf_metadata(conditions: &[super::Conditions]) -> Vec<Box<dyn common::ConditionMetadata>> {
+    conditions
+        .iter()
+        .flat_map(|conditions| conditions.metadata())
+        .collect()
+}
+
+/// Metadata for a compound condition. Aggregates the metadata of the nested leaf conditions.
+#[derive(Debug)]
+struct CompoundMetadata {
+    name: String,
+    children: Vec<Box<dyn common::ConditionMetadata>>,
+}
+
+impl CompoundMetadata {
+    fn new(name: &str, conditions: &[super::Conditions]) -> Self {
+        Self {
+            name: name.to_string(),
+            children: leaf_metadata(&conditions),
+        }
+    }
+}
+
+impl common::ConditionMetadata for CompoundMetadata {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn interactive_set_parameter(
+        &self,
+        parameters: &mut crate::lock::plan::conditions::Parameters,
+    ) -> Result<()> {
+        for child in &self.children {
+            if !child.is_parameter_set(&parameters) {
+                child.interactive_set_parameter(parameters)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, parameters: &crate::lock::plan::conditions::Parameters) -> bool {
+        self.children
+            .iter()
+            .all(|child| child.is_parameter_set(&parameters))
+    }
+}
+
+#[test]
+fn test_any_passes_if_one_passes() -> Result<()> {
+    use common::Condition;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.for_profit = Some(false);
+    parameters.employees_count = Some(200);
+
+    let conditions: Vec<super::Conditions> = serde_json::from_value(serde_json::json!([
+        {"for-profit": true},
+        {"employees-count": "150 <= count < 500"}
+    ]))?;
+    assert!(Any(conditions.clone()).evaluate(&parameters)?);
+    assert!(!All(conditions).evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_not_inverts() -> Result<()> {
+    use common::Condition;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.for_profit = Some(true);
+
+    let conditions: super::Conditions =
+        serde_json::from_value(serde_json::json!({"for-profit": true}))?;
+    assert!(!Not(Box::new(conditions)).evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_any_passes_with_undecidable_alternative() -> Result<()> {
+    use common::Condition;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.for_profit = Some(true);
+
+    let conditions: Vec<super::Conditions> = serde_json::from_value(serde_json::json!([
+        {"for-profit": true},
+        {"employees-count": "150 <= count < 500"}
+    ]))?;
+    assert!(Any(conditions.clone()).evaluate(&parameters)?);
+    assert!(All(conditions).evaluate(&parameters).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_metadata_includes_nested_conditions() -> Result<()> {
+    let conditions: super::Conditions = serde_json::from_value(serde_json::json!({
+        "for-profit": true,
+        "any": [
+            {"for-profit": true},
+            {"not": {"employees-count": "1 <= count < 50"}}
+        ]
+    }))?;
+    let names = conditions
+        .metadata()
+        .iter()
+        .map(|metadata| metadata.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["for-profit", "employees-count"]);
+    Ok(())
+}
+
+#[test]
+fn test_serde_round_trip() -> Result<()> {
+    let value = serde_json::json!({
+        "any": [
+            {"for-profit": true},
+            {"not": {"employees-count": "1 <= count < 50"}}
+        ]
+    });
+    let conditions: super::Conditions = serde_json::from_value(value.clone())?;
+    assert_eq!(serde_json::to_value(&conditions)?, value);
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use super::common;
+use anyhow::Result;
+
+/// ISO 3166-1 alpha-2 country codes.
+static COUNTRY_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// ISO 3166-1 alpha-2 country code. Example: "GB"
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct CountryCode(String);
+
+impl std::string::ToString for CountryCode {
+    fn to_string(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl Into<String> for CountryCode {
+    fn into(self) -> String {
+        self.0
+    }
+}
+
+impl std::convert::TryFrom<&str> for CountryCode {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let code = value.trim().to_uppercase();
+        if !COUNTRY_CODES.contains(&code.as_str()) {
+            return Err(anyhow::format_err!(
+                "Unknown ISO 3166-1 alpha-2 country code: {}",
+                value
+            ));
+        }
+        Ok(Self(code))
+    }
+}
+
+impl std::convert::TryFrom<String> for CountryCode {
+    type Error = anyhow::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+/// Country condition. Restricts a plan to (or exempts from a plan) the given countries.
+#[derive(Debug, Clone, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Country {
+    /// Plan applies only within these countries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<CountryCode>>,
+
+    /// Plan does not apply within these countries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<CountryCode>>,
+}
+
+impl Country {
+    pub fn new(include: &Vec<String>, exclude: &Vec<String>) -> Result<Self> {
+        let parse = |codes: &Vec<String>| -> Result<Option<Vec<CountryCode>>> {
+            if codes.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(
+                codes
+                    .iter()
+                    .map(|code| CountryCode::try_from(code.as_str()))
+                    .collect::<Result<Vec<_>>>()?,
+            ))
+        };
+        Ok(Self {
+            include: parse(&include)?,
+            exclude: parse(&exclude)?,
+        })
+    }
+
+    pub fn contains(&self, country: &CountryCode) -> bool {
+        let included = self
+            .include
+            .as_ref()
+            .map_or(true, |include| include.contains(&country));
+        let excluded = self
+            .exclude
+            .as_ref()
+            .map_or(false, |exclude| exclude.contains(&country));
+        included && !excluded
+    }
+}
+
+impl common::Condition for Country {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        let country = parameters.country.as_ref().ok_or(anyhow::format_err!(
+            "Attempting to evaluate plan conditions using unset parameter `{}`.",
+            self.metadata().name()
+        ))?;
+        Ok(self.contains(&country))
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(CountryMetadata) as Box<dyn common::ConditionMetadata>
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CountryMetadata;
+
+impl common::ConditionMetadata for CountryMetadata {
+    fn name(&self) -> String {
+        "country".to_string()
+    }
+
+    fn interactive_set_parameter(
+        &self,
+        parameters: &mut crate::lock::plan::conditions::Parameters,
+    ) -> Result<()> {
+        let country = dialoguer::Input::<String>::new()
+            .with_prompt("Country of your organization (ISO 3166-1 alpha-2 code, example: GB)")
+            .validate_with(|value: &String| -> Result<(), String> {
+                CountryCode::try_from(value.as_str())
+                    .map(|_| ())
+                    .map_err(|error| error.to_string())
+            })
+            .interact_text()?;
+        parameters.country = Some(CountryCode::try_from(country.as_str())?);
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, parameters: &crate::lock::plan::conditions::Parameters) -> bool {
+        parameters.country.is_some()
+    }
+}
+
+#[test]
+fn test_evaluate_include() -> Result<()> {
+    use common::Condition;
+
+    let condition = Country::new(&vec!["gb".to_string(), "US".to_string()], &vec![])?;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.country = Some(CountryCode::try_from("GB")?);
+    assert!(condition.evaluate(&parameters)?);
+
+    parameters.country = Some(CountryCode::try_from("FR")?);
+    assert!(!condition.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_evaluate_exclude() -> Result<()> {
+    use common::Condition;
+
+    let condition = Country::new(&vec![], &vec!["CU".to_string()])?;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.country = Some(CountryCode::try_from("CU")?);
+    assert!(!condition.evaluate(&parameters)?);
+
+    parameters.country = Some(CountryCode::try_from("FR")?);
+    assert!(condition.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_unknown_country_code() {
+    assert!(CountryCode::try_from("XX").is_err());
+    assert!(CountryCode::try_from("GBR").is_err());
+}
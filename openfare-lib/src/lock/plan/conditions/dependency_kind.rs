@@ -0,0 +1,90 @@
+use super::common;
+use crate::package;
+use anyhow::Result;
+
+/// Dependency kind condition. The plan applies if the package is depended upon in any of the
+/// given ways (example: runtime dependency only).
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DependencyKind(Vec<package::DependencyKind>);
+
+impl std::convert::TryFrom<&Vec<String>> for DependencyKind {
+    type Error = anyhow::Error;
+    fn try_from(values: &Vec<String>) -> Result<Self, Self::Error> {
+        let kinds = values
+            .iter()
+            .map(|value| value.parse::<package::DependencyKind>())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self(kinds))
+    }
+}
+
+impl common::Condition for DependencyKind {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        let dependency_kind = parameters
+            .dependency_kind
+            .as_ref()
+            .ok_or(anyhow::format_err!(
+                "Attempting to evaluate plan conditions using unset parameter `{}`.",
+                self.metadata().name()
+            ))?;
+        Ok(self.0.contains(&dependency_kind))
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(DependencyKindMetadata) as Box<dyn common::ConditionMetadata>
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DependencyKindMetadata;
+
+impl common::ConditionMetadata for DependencyKindMetadata {
+    fn name(&self) -> String {
+        "dependency-kind".to_string()
+    }
+
+    /// The dependency kind is reported by the extension for each package. It is not a user
+    /// profile parameter.
+    fn interactive_set_parameter(
+        &self,
+        _parameters: &mut crate::lock::plan::conditions::Parameters,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, _parameters: &crate::lock::plan::conditions::Parameters) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_evaluate_cases() -> Result<()> {
+    use common::Condition;
+
+    let dependency_kind = DependencyKind::try_from(&vec!["runtime".to_string()])?;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    assert!(dependency_kind.evaluate(&parameters).is_err());
+
+    parameters.dependency_kind = Some(package::DependencyKind::Runtime);
+    assert!(dependency_kind.evaluate(&parameters)?);
+
+    parameters.dependency_kind = Some(package::DependencyKind::Dev);
+    assert!(!dependency_kind.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_serde_round_trip() -> Result<()> {
+    let value = serde_json::json!(["runtime", "optional"]);
+    let dependency_kind: DependencyKind = serde_json::from_value(value.clone())?;
+    assert_eq!(
+        dependency_kind,
+        DependencyKind(vec![
+            package::DependencyKind::Runtime,
+            package::DependencyKind::Optional
+        ])
+    );
+    assert_eq!(serde_json::to_value(&dependency_kind)?, value);
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use super::common;
+use anyhow::Result;
+
+use chrono::Utc;
+
+/// Date from which the plan applies. Complements the expiration condition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EffectiveFrom {
+    time: chrono::DateTime<Utc>,
+}
+
+impl std::convert::TryFrom<&str> for EffectiveFrom {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let time = common::parse_date(&value)?;
+        Ok(Self { time })
+    }
+}
+
+impl common::Condition for EffectiveFrom {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        let current_time = parameters.current_time();
+        let effective_from = &self.time;
+        let result = common::evaluate_operator::<chrono::DateTime<Utc>>(
+            &current_time,
+            &common::Operator::GreaterThanEqual,
+            &effective_from,
+        );
+        Ok(result)
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(EffectiveFromMetadata) as Box<dyn common::ConditionMetadata>
+    }
+}
+
+impl serde::Serialize for EffectiveFrom {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(format!("{}", self.time.format("%Y-%m-%d"),).as_str())
+    }
+}
+
+struct Visitor {
+    marker: std::marker::PhantomData<fn() -> EffectiveFrom>,
+}
+
+impl Visitor {
+    fn new() -> Self {
+        Visitor {
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for Visitor {
+    type Value = EffectiveFrom;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a string such as '2022-01-31'")
+    }
+
+    fn visit_str<E>(self, value: &str) -> core::result::Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let time = common::parse_date(&value).map_err(|_| {
+            serde::de::Error::custom(serde::de::Unexpected::Other(
+                format!("Failed to parse effective-from date: {}", value).as_str(),
+            ))
+        })?;
+        Ok(Self::Value { time })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for EffectiveFrom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(Visitor::new())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EffectiveFromMetadata;
+
+impl common::ConditionMetadata for EffectiveFromMetadata {
+    fn name(&self) -> String {
+        "effective-from".to_string()
+    }
+
+    fn interactive_set_parameter(
+        &self,
+        _parameters: &mut crate::lock::plan::conditions::Parameters,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, _parameters: &crate::lock::plan::conditions::Parameters) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_evaluate_cases() -> Result<()> {
+    use common::Condition;
+    let condition = EffectiveFrom::try_from("2030-01-31")?;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.set_evaluation_date("2030-01-30")?;
+    assert!(!condition.evaluate(&parameters)?);
+
+    parameters.set_evaluation_date("2030-01-31")?;
+    assert!(condition.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_serde_round_trip() -> Result<()> {
+    let value = serde_json::json!("2030-01-31");
+    let condition: EffectiveFrom = serde_json::from_value(value.clone())?;
+    assert_eq!(condition, EffectiveFrom::try_from("2030-01-31")?);
+    assert_eq!(serde_json::to_value(&condition)?, value);
+    Ok(())
+}
@@ -3,8 +3,18 @@ use anyhow::Result;
 
 use strum::IntoEnumIterator;
 
+/// Employees count condition.
+///
+/// Either a comparison against the exact employees count (example: ">= 250") or one of the
+/// legacy fixed ranges (example: "1 <= count < 50").
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct EmployeesCount(Range);
+#[serde(into = "String", try_from = "String")]
+pub enum EmployeesCount {
+    Range(Range),
+    Comparison(common::Operator, Count),
+}
+
+pub type Count = u64;
 
 #[derive(
     Debug,
@@ -27,8 +37,16 @@ pub enum Range {
 }
 
 impl Range {
-    pub fn evaluate(&self, employees_count: &Self) -> bool {
-        self == employees_count
+    /// Returns true if the range contains the given employees count.
+    pub fn contains(&self, employees_count: &Count) -> bool {
+        let (lower, upper) = match self {
+            Self::GreaterEqual1To50 => (1, Some(50)),
+            Self::GreaterEqual50To150 => (50, Some(150)),
+            Self::GreaterEqual150To500 => (150, Some(500)),
+            Self::GreaterEqual500To1000 => (500, Some(1000)),
+            Self::GreaterEqual1000 => (1000, None),
+        };
+        *employees_count >= lower && upper.map_or(true, |upper| *employees_count < upper)
     }
 }
 
@@ -74,16 +92,63 @@ impl std::convert::TryFrom<&str> for Range {
 impl std::convert::TryFrom<String> for Range {
     type Error = anyhow::Error;
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        for range in Self::iter() {
-            if range.to_string().as_str() == value {
-                return Ok(range);
+        Self::try_from(value.as_str())
+    }
+}
+
+impl EmployeesCount {
+    pub fn matches_count(&self, employees_count: &Count) -> bool {
+        match self {
+            Self::Range(range) => range.contains(&employees_count),
+            Self::Comparison(operator, count) => {
+                common::evaluate_operator(employees_count, &operator, &count)
+            }
+        }
+    }
+}
+
+impl std::string::ToString for EmployeesCount {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Range(range) => range.to_string(),
+            Self::Comparison(operator, count) => format!("{} {}", operator.to_string(), count),
+        }
+    }
+}
+
+impl Into<String> for EmployeesCount {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::convert::TryFrom<&str> for EmployeesCount {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Ok(range) = Range::try_from(value) {
+            return Ok(Self::Range(range));
+        }
+
+        let value = value.trim();
+        // Check two character operators first.
+        for operator in &[">=", "<=", ">", "<", "="] {
+            if let Some(count) = value.strip_prefix(*operator) {
+                let count = count
+                    .trim()
+                    .parse::<Count>()
+                    .map_err(|_| anyhow::format_err!("Error parsing employees count: {}", value))?;
+                return Ok(Self::Comparison(
+                    common::Operator::try_from(*operator)?,
+                    count,
+                ));
             }
         }
 
         let error_message = format!(
-            "Error parsing employees count range: {}\nAccepted values:\n{}",
+            "Error parsing employees count: {}\n\
+            Expected an operator and count (example: \">= 250\") or one of:\n{}",
             value,
-            Self::iter()
+            Range::iter()
                 .map(|range| range.to_string())
                 .collect::<Vec<String>>()
                 .join("\n")
@@ -92,10 +157,10 @@ impl std::convert::TryFrom<String> for Range {
     }
 }
 
-impl std::convert::TryFrom<&str> for EmployeesCount {
+impl std::convert::TryFrom<String> for EmployeesCount {
     type Error = anyhow::Error;
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Ok(Self(Range::try_from(value)?))
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
     }
 }
 
@@ -108,7 +173,7 @@ impl common::Condition for EmployeesCount {
                 "Attempting to evaluate plan conditions using unset parameter `{}`.",
                 self.metadata().name()
             ))?;
-        Ok(self.0.evaluate(&employees_count))
+        Ok(self.matches_count(&employees_count))
     }
 
     fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
@@ -128,14 +193,10 @@ impl common::ConditionMetadata for EmployeesCountMetadata {
         &self,
         parameters: &mut crate::lock::plan::conditions::Parameters,
     ) -> Result<()> {
-        println!("Select a range for the number of employees (count) within your organization:");
-        let ranges = Range::iter().collect::<Vec<_>>();
-        let items = ranges.iter().map(|r| r.to_string()).collect::<Vec<_>>();
-        let index = dialoguer::Select::new().items(&items).interact()?;
-
-        if let Some(range) = ranges.get(index) {
-            parameters.employees_count = Some(range.clone());
-        }
+        let employees_count = dialoguer::Input::<Count>::new()
+            .with_prompt("Number of employees within your organization")
+            .interact_text()?;
+        parameters.employees_count = Some(employees_count);
         Ok(())
     }
 
@@ -151,9 +212,45 @@ fn test_from_str() -> Result<()> {
     let range = Range::GreaterEqual1To50;
 
     let mut parameters = crate::lock::plan::conditions::Parameters::default();
-    parameters.employees_count = Some(range.clone());
+    parameters.employees_count = Some(10);
 
     let employees_count = EmployeesCount::try_from(range.to_string().as_str())?;
+    assert_eq!(employees_count, EmployeesCount::Range(range));
+    assert!(employees_count.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_evaluate_comparison() -> Result<()> {
+    use common::Condition;
+
+    let employees_count = EmployeesCount::try_from(">= 250")?;
+    assert_eq!(
+        employees_count,
+        EmployeesCount::Comparison(common::Operator::GreaterThanEqual, 250)
+    );
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.employees_count = Some(250);
     assert!(employees_count.evaluate(&parameters)?);
+
+    parameters.employees_count = Some(249);
+    assert!(!employees_count.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_serialize() -> Result<()> {
+    let employees_count = EmployeesCount::try_from("<50")?;
+    assert_eq!(serde_json::to_value(&employees_count)?, "< 50");
+
+    let employees_count = EmployeesCount::try_from("50 <= count < 150")?;
+    assert_eq!(serde_json::to_value(&employees_count)?, "50 <= count < 150");
     Ok(())
 }
+
+#[test]
+fn test_parse_invalid() {
+    assert!(EmployeesCount::try_from("about 100").is_err());
+    assert!(EmployeesCount::try_from(">= lots").is_err());
+}
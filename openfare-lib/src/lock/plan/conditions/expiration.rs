@@ -1,7 +1,7 @@
 use super::common;
 use anyhow::Result;
 
-use chrono::{TimeZone, Utc};
+use chrono::Utc;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Expiration {
@@ -11,14 +11,14 @@ pub struct Expiration {
 impl std::convert::TryFrom<&str> for Expiration {
     type Error = anyhow::Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let time = parse_value(&value)?;
+        let time = common::parse_date(&value)?;
         Ok(Self { time })
     }
 }
 
 impl common::Condition for Expiration {
-    fn evaluate(&self, _parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
-        let current_time = chrono::offset::Utc::now();
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        let current_time = parameters.current_time();
         let expiration = &self.time;
         let result = common::evaluate_operator::<chrono::DateTime<Utc>>(
             &current_time,
@@ -65,7 +65,11 @@ impl<'de> serde::de::Visitor<'de> for Visitor {
     where
         E: serde::de::Error,
     {
-        let time = parse_value(&value).expect("parse expiration condition value");
+        let time = common::parse_date(&value).map_err(|_| {
+            serde::de::Error::custom(serde::de::Unexpected::Other(
+                format!("Failed to parse expiration date: {}", value).as_str(),
+            ))
+        })?;
         Ok(Self::Value { time })
     }
 }
@@ -99,26 +103,6 @@ impl common::ConditionMetadata for ExpirationMetadata {
     }
 }
 
-fn parse_value(value: &str) -> Result<chrono::DateTime<Utc>> {
-    let date = chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d")?;
-    let time = naive_date_to_utc(&date)?;
-    Ok(time)
-}
-
-fn naive_date_to_utc(date: &chrono::NaiveDate) -> Result<chrono::DateTime<Utc>> {
-    // The known 1 hour time offset in seconds
-    let tz_offset = chrono::FixedOffset::east(0);
-    // The known time
-    let time = chrono::NaiveTime::from_hms(0, 0, 0);
-    // Naive date time, with no time zone information
-    let datetime = chrono::NaiveDateTime::new(date.clone(), time);
-
-    let dt_with_tz: chrono::DateTime<chrono::FixedOffset> =
-        tz_offset.from_local_datetime(&datetime).unwrap();
-    let dt_with_tz_utc: chrono::DateTime<Utc> = Utc.from_utc_datetime(&dt_with_tz.naive_utc());
-    Ok(dt_with_tz_utc)
-}
-
 #[test]
 fn test_evaluate_cases() -> Result<()> {
     use common::Condition;
@@ -134,9 +118,29 @@ fn test_parse_str() -> Result<()> {
 
     let expected_date = chrono::NaiveDate::parse_from_str("2022-01-31", "%Y-%m-%d")?;
     let expected = Expiration {
-        time: naive_date_to_utc(&expected_date)?,
+        time: common::naive_date_to_utc(&expected_date)?,
     };
 
     assert_eq!(result, expected);
     Ok(())
 }
+
+#[test]
+fn test_evaluate_at_given_time() -> Result<()> {
+    use common::Condition;
+    let condition = Expiration::try_from("2030-01-31")?;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.set_evaluation_date("2030-01-30")?;
+    assert!(condition.evaluate(&parameters)?);
+
+    parameters.set_evaluation_date("2030-01-31")?;
+    assert!(!condition.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_deserialize_invalid_date_is_error() {
+    let result = serde_json::from_str::<Expiration>("\"2022-31-01\"");
+    assert!(result.is_err());
+}
@@ -1,16 +1,28 @@
-use anyhow::Result;
-
+mod annual_revenue;
 mod common;
+mod compound;
+mod country;
+mod dependency_kind;
+mod effective_from;
 mod employees_count;
 mod expiration;
 mod for_profit;
 pub mod parameters;
+mod usage;
+mod version;
 
-pub use common::{Condition, ConditionMetadata};
+pub use annual_revenue::AnnualRevenue;
+pub use common::{Condition, ConditionMetadata, Evaluation};
+pub use compound::{All, Any, Not};
+pub use country::{Country, CountryCode};
+pub use dependency_kind::DependencyKind;
+pub use effective_from::EffectiveFrom;
 pub use employees_count::EmployeesCount;
 pub use expiration::Expiration;
 pub use for_profit::ForProfit;
 pub use parameters::Parameters;
+pub use usage::Usage;
+pub use version::Version;
 
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Conditions {
@@ -20,8 +32,38 @@ pub struct Conditions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expiration: Option<Expiration>,
 
+    #[serde(skip_serializing_if = "Option::is_none", rename = "effective-from")]
+    pub effective_from: Option<EffectiveFrom>,
+
     #[serde(skip_serializing_if = "Option::is_none", rename = "employees-count")]
     pub employees_count: Option<EmployeesCount>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "annual-revenue")]
+    pub annual_revenue: Option<AnnualRevenue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<Country>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "dependency-kind")]
+    pub dependency_kind: Option<DependencyKind>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+
+    /// Passes if all nested conditions pass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all: Option<All>,
+
+    /// Passes if any nested conditions pass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub any: Option<Any>,
+
+    /// Passes if nested conditions do not pass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not: Option<Not>,
 }
 
 impl Conditions {
@@ -33,25 +75,63 @@ impl Conditions {
         if let Some(expiration) = &self.expiration {
             vec.push(Box::new(expiration.clone()) as Box<dyn Condition>);
         }
+        if let Some(effective_from) = &self.effective_from {
+            vec.push(Box::new(effective_from.clone()) as Box<dyn Condition>);
+        }
         if let Some(employees_count) = &self.employees_count {
             vec.push(Box::new(employees_count.clone()) as Box<dyn Condition>);
         }
+        if let Some(annual_revenue) = &self.annual_revenue {
+            vec.push(Box::new(annual_revenue.clone()) as Box<dyn Condition>);
+        }
+        if let Some(country) = &self.country {
+            vec.push(Box::new(country.clone()) as Box<dyn Condition>);
+        }
+        if let Some(usage) = &self.usage {
+            vec.push(Box::new(usage.clone()) as Box<dyn Condition>);
+        }
+        if let Some(dependency_kind) = &self.dependency_kind {
+            vec.push(Box::new(dependency_kind.clone()) as Box<dyn Condition>);
+        }
+        if let Some(version) = &self.version {
+            vec.push(Box::new(version.clone()) as Box<dyn Condition>);
+        }
+        if let Some(all) = &self.all {
+            vec.push(Box::new(all.clone()) as Box<dyn Condition>);
+        }
+        if let Some(any) = &self.any {
+            vec.push(Box::new(any.clone()) as Box<dyn Condition>);
+        }
+        if let Some(not) = &self.not {
+            vec.push(Box::new(not.clone()) as Box<dyn Condition>);
+        }
         vec
     }
 
+    /// Returns unique metadata for all leaf conditions, including those nested within
+    /// compound conditions.
     pub fn metadata(&self) -> Vec<Box<dyn ConditionMetadata>> {
-        self.as_vec()
+        let mut result: Vec<Box<dyn ConditionMetadata>> = vec![];
+        for metadata in self
+            .as_vec()
             .iter()
-            .map(|condition| condition.metadata())
-            .collect()
+            .flat_map(|condition| condition.leaf_metadata())
+        {
+            if !result.iter().any(|m| m.name() == metadata.name()) {
+                result.push(metadata);
+            }
+        }
+        result
     }
 
-    pub fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
-        let all_conditions_pass = self
-            .as_vec()
-            .iter()
-            .all(|condition| condition.evaluate(parameters).unwrap_or(false));
-        Ok(all_conditions_pass)
+    /// Evaluates conditions. Conditions which can not be evaluated (e.g. due to an unset
+    /// parameter) are undecidable rather than failing silently.
+    pub fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Evaluation {
+        Evaluation::all(
+            self.as_vec()
+                .iter()
+                .map(|condition| Evaluation::from_result(condition.evaluate(&parameters))),
+        )
     }
 
     pub fn set_some(&mut self, incoming: &Self) {
@@ -61,8 +141,138 @@ impl Conditions {
         if self.expiration.is_none() {
             self.expiration = incoming.expiration.clone();
         }
+        if self.effective_from.is_none() {
+            self.effective_from = incoming.effective_from.clone();
+        }
         if self.employees_count.is_none() {
             self.employees_count = incoming.employees_count.clone();
         }
+        if self.annual_revenue.is_none() {
+            self.annual_revenue = incoming.annual_revenue.clone();
+        }
+        if self.country.is_none() {
+            self.country = incoming.country.clone();
+        }
+        if self.usage.is_none() {
+            self.usage = incoming.usage.clone();
+        }
+        if self.dependency_kind.is_none() {
+            self.dependency_kind = incoming.dependency_kind.clone();
+        }
+        if self.version.is_none() {
+            self.version = incoming.version.clone();
+        }
+        if self.all.is_none() {
+            self.all = incoming.all.clone();
+        }
+        if self.any.is_none() {
+            self.any = incoming.any.clone();
+        }
+        if self.not.is_none() {
+            self.not = incoming.not.clone();
+        }
+    }
+
+    /// Splits into separate sets of conditions, each containing a single condition.
+    pub fn split(&self) -> Vec<Self> {
+        let mut result = vec![];
+        if let Some(for_profit) = &self.for_profit {
+            result.push(Self {
+                for_profit: Some(for_profit.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(expiration) = &self.expiration {
+            result.push(Self {
+                expiration: Some(expiration.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(effective_from) = &self.effective_from {
+            result.push(Self {
+                effective_from: Some(effective_from.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(employees_count) = &self.employees_count {
+            result.push(Self {
+                employees_count: Some(employees_count.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(annual_revenue) = &self.annual_revenue {
+            result.push(Self {
+                annual_revenue: Some(annual_revenue.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(country) = &self.country {
+            result.push(Self {
+                country: Some(country.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(usage) = &self.usage {
+            result.push(Self {
+                usage: Some(usage.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(dependency_kind) = &self.dependency_kind {
+            result.push(Self {
+                dependency_kind: Some(dependency_kind.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(version) = &self.version {
+            result.push(Self {
+                version: Some(version.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(all) = &self.all {
+            result.push(Self {
+                all: Some(all.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(any) = &self.any {
+            result.push(Self {
+                any: Some(any.clone()),
+                ..Default::default()
+            });
+        }
+        if let Some(not) = &self.not {
+            result.push(Self {
+                not: Some(not.clone()),
+                ..Default::default()
+            });
+        }
+        result
+    }
+
+    /// Adds a compound condition which must pass in addition to the existing conditions.
+    pub fn push(&mut self, conditions: Self) {
+        self.all.get_or_insert_with(All::default).0.push(conditions);
     }
 }
+
+#[test]
+fn test_evaluate_unset_parameter_is_undecidable() -> anyhow::Result<()> {
+    let conditions: Conditions = serde_json::from_value(serde_json::json!({
+        "for-profit": true,
+        "employees-count": "1 <= count < 50"
+    }))?;
+
+    let mut parameters = Parameters::default();
+    parameters.for_profit = Some(true);
+    assert!(matches!(
+        conditions.evaluate(&parameters),
+        Evaluation::Undecidable(_)
+    ));
+
+    // A failing condition decides the outcome regardless of unset parameters.
+    parameters.for_profit = Some(false);
+    assert_eq!(conditions.evaluate(&parameters), Evaluation::Fail);
+    Ok(())
+}
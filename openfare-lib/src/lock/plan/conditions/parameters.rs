@@ -3,26 +3,88 @@ use anyhow::Result;
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Parameters {
-    #[serde(rename = "employees-count")]
-    pub employees_count: Option<super::employees_count::Range>,
+    #[serde(
+        rename = "employees-count",
+        default,
+        deserialize_with = "deserialize_employees_count"
+    )]
+    pub employees_count: Option<super::employees_count::Count>,
+
+    #[serde(rename = "annual-revenue")]
+    pub annual_revenue: Option<super::annual_revenue::Range>,
+
+    pub country: Option<super::country::CountryCode>,
+
+    pub usage: Option<Vec<super::usage::Scope>>,
 
     #[serde(rename = "for-profit")]
     pub for_profit: Option<bool>,
 
     #[serde(rename = "include-voluntary-donations")]
     pub include_voluntary_plans: bool,
+
+    /// Counts for per-unit plan prices. Example: number of developer seats.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub units:
+        std::collections::BTreeMap<crate::lock::plan::unit::Unit, crate::lock::plan::unit::Count>,
+
+    /// Time at which date conditions are evaluated. Current time if unset.
+    #[serde(skip)]
+    pub evaluation_time: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Kind of the dependency for which conditions are evaluated. Set per package.
+    #[serde(skip)]
+    pub dependency_kind: Option<crate::package::DependencyKind>,
+
+    /// Package for which conditions are evaluated. Set per package.
+    #[serde(skip)]
+    pub package: Option<crate::package::Package>,
 }
 
 impl std::default::Default for Parameters {
     fn default() -> Self {
         Self {
             employees_count: None,
+            annual_revenue: None,
+            country: None,
+            usage: None,
             for_profit: None,
             include_voluntary_plans: true,
+            units: std::collections::BTreeMap::new(),
+            evaluation_time: None,
+            dependency_kind: None,
+            package: None,
         }
     }
 }
 
+impl Parameters {
+    /// Returns the time at which date conditions are evaluated.
+    pub fn current_time(&self) -> chrono::DateTime<chrono::Utc> {
+        self.evaluation_time
+            .unwrap_or_else(chrono::offset::Utc::now)
+    }
+
+    /// Evaluate date conditions at the given date instead of the current time.
+    /// Example: "2027-01-01"
+    pub fn set_evaluation_date(&mut self, date: &str) -> Result<()> {
+        self.evaluation_time = Some(common::parse_date(&date)?);
+        Ok(())
+    }
+
+    /// Returns parameters for evaluating the conditions of the given package.
+    pub fn for_package(
+        &self,
+        package: &crate::package::Package,
+        dependency_kind: &crate::package::DependencyKind,
+    ) -> Self {
+        let mut parameters = self.clone();
+        parameters.package = Some(package.clone());
+        parameters.dependency_kind = Some(dependency_kind.clone());
+        parameters
+    }
+}
+
 impl std::fmt::Display for Parameters {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -33,6 +95,35 @@ impl std::fmt::Display for Parameters {
     }
 }
 
+/// Deserialize exact employees count.
+///
+/// Legacy employees count ranges (example: "1 <= count < 50") are discarded so that the
+/// user is prompted for an exact count.
+fn deserialize_employees_count<'de, D>(
+    deserializer: D,
+) -> Result<Option<super::employees_count::Count>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Count(super::employees_count::Count),
+        Range(String),
+    }
+
+    Ok(
+        match <Option<Value> as serde::Deserialize>::deserialize(deserializer)? {
+            Some(Value::Count(count)) => Some(count),
+            Some(Value::Range(range)) => {
+                log::debug!("Discarding legacy employees count range: {}", range);
+                None
+            }
+            None => None,
+        },
+    )
+}
+
 /// Check correct parameters set for the given package locks conditions.
 /// Attempts to set parameters if they are not set.
 ///
@@ -55,3 +146,21 @@ pub fn check_set(
     }
     Ok(parameter_set_correct)
 }
+
+#[test]
+fn test_deserialize_legacy_employees_count() -> Result<()> {
+    let parameters: Parameters = serde_json::from_value(serde_json::json!({
+        "employees-count": "1 <= count < 50",
+        "for-profit": true,
+        "include-voluntary-donations": true
+    }))?;
+    assert_eq!(parameters.employees_count, None);
+
+    let parameters: Parameters = serde_json::from_value(serde_json::json!({
+        "employees-count": 120,
+        "for-profit": true,
+        "include-voluntary-donations": true
+    }))?;
+    assert_eq!(parameters.employees_count, Some(120));
+    Ok(())
+}
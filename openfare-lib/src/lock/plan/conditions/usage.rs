@@ -0,0 +1,167 @@
+use super::common;
+use anyhow::Result;
+
+use strum::IntoEnumIterator;
+
+/// Usage condition. The plan applies if the software is used in any of the given ways.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Usage(Vec<Scope>);
+
+/// How the software is used.
+#[derive(
+    Debug,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(into = "String", try_from = "String")]
+pub enum Scope {
+    /// Used internally within the organization.
+    Internal,
+    /// Used to provide a hosted service (e.g. SaaS).
+    HostedService,
+    /// Redistributed to third parties (e.g. within a product).
+    Redistributed,
+    /// Embedded within a hardware device.
+    EmbeddedDevice,
+}
+
+impl std::string::ToString for Scope {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Internal => "internal",
+            Self::HostedService => "hosted-service",
+            Self::Redistributed => "redistributed",
+            Self::EmbeddedDevice => "embedded-device",
+        }
+        .to_string()
+    }
+}
+
+impl Into<String> for Scope {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::convert::TryFrom<&str> for Scope {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        for scope in Self::iter() {
+            if scope.to_string().as_str() == value {
+                return Ok(scope);
+            }
+        }
+        let error_message = format!(
+            "Error parsing usage: {}\nAccepted values:\n{}",
+            value,
+            Self::iter()
+                .map(|scope| scope.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+        Err(anyhow::format_err!(error_message))
+    }
+}
+
+impl std::convert::TryFrom<String> for Scope {
+    type Error = anyhow::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl std::convert::TryFrom<&Vec<String>> for Usage {
+    type Error = anyhow::Error;
+    fn try_from(values: &Vec<String>) -> Result<Self, Self::Error> {
+        let scopes = values
+            .iter()
+            .map(|value| Scope::try_from(value.as_str()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self(scopes))
+    }
+}
+
+impl common::Condition for Usage {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        let usage = parameters.usage.as_ref().ok_or(anyhow::format_err!(
+            "Attempting to evaluate plan conditions using unset parameter `{}`.",
+            self.metadata().name()
+        ))?;
+        Ok(usage.iter().any(|scope| self.0.contains(&scope)))
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(UsageMetadata) as Box<dyn common::ConditionMetadata>
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UsageMetadata;
+
+impl common::ConditionMetadata for UsageMetadata {
+    fn name(&self) -> String {
+        "usage".to_string()
+    }
+
+    fn interactive_set_parameter(
+        &self,
+        parameters: &mut crate::lock::plan::conditions::Parameters,
+    ) -> Result<()> {
+        println!("Select all the ways in which your organization uses the software:");
+        let scopes = Scope::iter().collect::<Vec<_>>();
+        let items = scopes.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        loop {
+            let indexes = dialoguer::MultiSelect::new().items(&items).interact()?;
+            if indexes.is_empty() {
+                println!("Please select at least one.");
+                continue;
+            }
+            parameters.usage = Some(
+                indexes
+                    .iter()
+                    .filter_map(|index| scopes.get(*index))
+                    .cloned()
+                    .collect(),
+            );
+            break;
+        }
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, parameters: &crate::lock::plan::conditions::Parameters) -> bool {
+        parameters.usage.is_some()
+    }
+}
+
+#[test]
+fn test_evaluate_cases() -> Result<()> {
+    use common::Condition;
+
+    let usage = Usage::try_from(&vec![
+        "hosted-service".to_string(),
+        "redistributed".to_string(),
+    ])?;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    parameters.usage = Some(vec![Scope::Internal]);
+    assert!(!usage.evaluate(&parameters)?);
+
+    parameters.usage = Some(vec![Scope::Internal, Scope::HostedService]);
+    assert!(usage.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_serde_round_trip() -> Result<()> {
+    let value = serde_json::json!(["internal", "embedded-device"]);
+    let usage: Usage = serde_json::from_value(value.clone())?;
+    assert_eq!(usage, Usage(vec![Scope::Internal, Scope::EmbeddedDevice]));
+    assert_eq!(serde_json::to_value(&usage)?, value);
+    Ok(())
+}
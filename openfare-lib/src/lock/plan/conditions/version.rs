@@ -0,0 +1,116 @@
+use super::common;
+use anyhow::Result;
+
+/// Package version condition. The plan applies if the version of the package satisfies the
+/// given semver requirement. Example: ">=3.0.0"
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Version(semver::VersionReq);
+
+impl std::convert::TryFrom<&str> for Version {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let requirement = semver::VersionReq::parse(value).map_err(|error| {
+            anyhow::format_err!(
+                "Error parsing version requirement: {}\n{}\nExample: \">=3.0.0\"",
+                value,
+                error
+            )
+        })?;
+        Ok(Self(requirement))
+    }
+}
+
+impl common::Condition for Version {
+    fn evaluate(&self, parameters: &crate::lock::plan::conditions::Parameters) -> Result<bool> {
+        let package = parameters.package.as_ref().ok_or(anyhow::format_err!(
+            "Attempting to evaluate plan conditions using unset parameter `{}`.",
+            self.metadata().name()
+        ))?;
+        let version = parse_version(&package.version)?;
+        Ok(self.0.matches(&version))
+    }
+
+    fn metadata(&self) -> Box<dyn common::ConditionMetadata> {
+        Box::new(VersionMetadata) as Box<dyn common::ConditionMetadata>
+    }
+}
+
+/// Parse package version. Missing minor and patch numbers are assumed to be zero.
+/// Example: "3.1" is parsed as "3.1.0"
+fn parse_version(version: &str) -> Result<semver::Version> {
+    let version = version.trim().trim_start_matches('v');
+    if let Ok(version) = semver::Version::parse(&version) {
+        return Ok(version);
+    }
+
+    let components = version.split('.').collect::<Vec<_>>();
+    if components.len() < 3 && components.iter().all(|c| c.parse::<u64>().is_ok()) {
+        let mut components = components.clone();
+        components.resize(3, "0");
+        if let Ok(version) = semver::Version::parse(&components.join(".")) {
+            return Ok(version);
+        }
+    }
+    Err(anyhow::format_err!(
+        "Failed to parse package version as semver: {}",
+        version
+    ))
+}
+
+#[derive(Debug, Clone)]
+struct VersionMetadata;
+
+impl common::ConditionMetadata for VersionMetadata {
+    fn name(&self) -> String {
+        "version".to_string()
+    }
+
+    /// The package version is given by the package being priced. It is not a user profile
+    /// parameter.
+    fn interactive_set_parameter(
+        &self,
+        _parameters: &mut crate::lock::plan::conditions::Parameters,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, _parameters: &crate::lock::plan::conditions::Parameters) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_evaluate_cases() -> Result<()> {
+    use common::Condition;
+
+    let version = Version::try_from(">=3.0.0")?;
+
+    let mut parameters = crate::lock::plan::conditions::Parameters::default();
+    assert!(version.evaluate(&parameters).is_err());
+
+    let mut package = crate::package::Package {
+        registry: "registry.npmjs.org".to_string(),
+        name: "d3".to_string(),
+        version: "3.2.1".to_string(),
+    };
+    parameters.package = Some(package.clone());
+    assert!(version.evaluate(&parameters)?);
+
+    package.version = "2.9.0".to_string();
+    parameters.package = Some(package.clone());
+    assert!(!version.evaluate(&parameters)?);
+
+    package.version = "3.1".to_string();
+    parameters.package = Some(package.clone());
+    assert!(version.evaluate(&parameters)?);
+    Ok(())
+}
+
+#[test]
+fn test_serde_round_trip() -> Result<()> {
+    let value = serde_json::json!(">=3.0.0, <4.0.0");
+    let version: Version = serde_json::from_value(value.clone())?;
+    assert_eq!(version, Version::try_from(">=3.0.0, <4.0.0")?);
+    assert_eq!(serde_json::to_value(&version)?, value);
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use anyhow::{format_err, Result};
 
+pub mod billing_period;
 pub mod conditions;
+pub mod selection_policy;
+pub mod unit;
 
 use super::payee;
 
@@ -21,13 +24,23 @@ pub fn next_id(plans: &Plans) -> Result<Id> {
     Ok(ids.len().to_string())
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PlanType {
     Compulsory,
     Voluntary,
 }
 
+impl std::fmt::Display for PlanType {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plan_type = match self {
+            Self::Compulsory => "compulsory",
+            Self::Voluntary => "voluntary",
+        };
+        write!(formatter, "{}", plan_type)
+    }
+}
+
 impl std::str::FromStr for PlanType {
     type Err = anyhow::Error;
     fn from_str(value: &str) -> std::result::Result<Self, anyhow::Error> {
@@ -50,37 +63,135 @@ pub struct Plan {
     pub r#type: PlanType,
     pub conditions: conditions::Conditions,
 
-    /// The price of the plan.
+    /// The price of the plan. Price per unit if a unit is given.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub price: Option<crate::price::Price>,
+
+    /// Unit of a per-unit price. Example: per developer seat.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<unit::Unit>,
+
+    /// How often the price is paid. One-off if unset.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "billing-period")]
+    pub billing_period: Option<billing_period::BillingPeriod>,
+
+    /// Maintainer-declared priority used by the priority plan selection policy. Lower numbers
+    /// are preferred.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
 }
 
 impl Plan {
     pub fn is_applicable(
         &self,
         parameters: &crate::lock::plan::conditions::Parameters,
-    ) -> Result<bool> {
+    ) -> Result<conditions::Evaluation> {
         Ok(match self.r#type {
             PlanType::Voluntary => {
                 // Voluntary plans are subject to conditions.
-                parameters.include_voluntary_plans && self.conditions.evaluate(&parameters)?
+                if parameters.include_voluntary_plans {
+                    self.conditions.evaluate(&parameters)
+                } else {
+                    conditions::Evaluation::Fail
+                }
             }
-            PlanType::Compulsory => self.conditions.evaluate(&parameters)?,
+            PlanType::Compulsory => self.conditions.evaluate(&parameters),
         })
     }
+
+    /// Returns metadata for the parameters required by the plan's conditions and unit.
+    pub fn metadata(&self) -> Vec<Box<dyn conditions::ConditionMetadata>> {
+        let mut metadata = self.conditions.metadata();
+        if let Some(unit) = &self.unit {
+            metadata.push(unit.metadata());
+        }
+        metadata
+    }
+
+    /// Returns the total price of the plan. Per-unit prices are multiplied by the number of
+    /// units given in the parameters.
+    pub fn total_price(
+        &self,
+        parameters: &crate::lock::plan::conditions::Parameters,
+    ) -> Result<Option<crate::price::Price>> {
+        let price = match &self.price {
+            Some(price) => price,
+            None => return Ok(None),
+        };
+        let unit = match &self.unit {
+            Some(unit) => unit,
+            None => return Ok(Some(price.clone())),
+        };
+        let count = parameters.units.get(&unit).ok_or(format_err!(
+            "Unset plan unit count parameter: {}",
+            unit.to_string()
+        ))?;
+        let quantity = price
+            .quantity
+            .checked_mul(crate::price::Quantity::from(unit.units(&count)?))
+            .ok_or(format_err!(
+                "Plan price overflow: {} per {} for {} units",
+                price,
+                unit.to_string(),
+                count
+            ))?;
+        Ok(Some(crate::price::Price {
+            quantity,
+            currency: price.currency.clone(),
+        }))
+    }
+
+    /// Returns the unit of a per-unit priced plan if its count is not given in the parameters.
+    fn unset_unit<'a>(
+        &'a self,
+        parameters: &crate::lock::plan::conditions::Parameters,
+    ) -> Option<&'a unit::Unit> {
+        self.price.as_ref()?;
+        let unit = self.unit.as_ref()?;
+        if parameters.units.contains_key(&unit) {
+            None
+        } else {
+            Some(unit)
+        }
+    }
+}
+
+/// Plans partitioned by applicability.
+#[derive(Debug, Default, Clone)]
+pub struct FilteredPlans {
+    pub applicable: Plans,
+
+    /// Plans which could not be evaluated and the corresponding reasons.
+    pub undecidable: std::collections::BTreeMap<Id, String>,
 }
 
 /// Filter for applicable plans.
 pub fn filter_applicable(
     plans: &Plans,
     parameters: &crate::lock::plan::conditions::Parameters,
-) -> Result<Plans> {
+) -> Result<FilteredPlans> {
     // TODO: Return None if no applicable plans found.
-    let mut applicable_plans = Plans::new();
+    let mut filtered_plans = FilteredPlans::default();
     for (plan_id, plan) in plans {
-        if plan.is_applicable(&parameters)? {
-            applicable_plans.insert(plan_id.clone(), plan.clone());
+        match plan.is_applicable(&parameters)? {
+            conditions::Evaluation::Pass => match plan.unset_unit(&parameters) {
+                Some(unit) => {
+                    let reason = format!("unit count for {} not set", unit.to_string());
+                    log::debug!("Plan {} undecidable: {}", plan_id, reason);
+                    filtered_plans.undecidable.insert(plan_id.clone(), reason);
+                }
+                None => {
+                    filtered_plans
+                        .applicable
+                        .insert(plan_id.clone(), plan.clone());
+                }
+            },
+            conditions::Evaluation::Fail => {}
+            conditions::Evaluation::Undecidable(reason) => {
+                log::debug!("Plan {} undecidable: {}", plan_id, reason);
+                filtered_plans.undecidable.insert(plan_id.clone(), reason);
+            }
         }
     }
-    Ok(applicable_plans)
+    Ok(filtered_plans)
 }
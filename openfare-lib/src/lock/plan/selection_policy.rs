@@ -0,0 +1,234 @@
+use anyhow::Result;
+
+use strum::IntoEnumIterator;
+
+/// How a package's applicable plans are combined into the price paid.
+#[derive(
+    Debug,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(into = "String", try_from = "String")]
+pub enum SelectionPolicy {
+    /// Select the applicable plan with the lowest price.
+    Cheapest,
+    /// Select the applicable plan with the highest price.
+    MostExpensive,
+    /// Select all applicable plans. Prices are summed.
+    Cumulative,
+    /// Select the applicable plan with the highest maintainer-declared priority.
+    Priority,
+}
+
+impl std::default::Default for SelectionPolicy {
+    fn default() -> Self {
+        Self::MostExpensive
+    }
+}
+
+impl SelectionPolicy {
+    /// Selects plans from the given applicable plans.
+    ///
+    /// Where compulsory plans are applicable, a plan is selected from amongst the compulsory
+    /// plans only. A voluntary plan is therefore never selected in place of a compulsory plan.
+    ///
+    /// Plan prices are compared in the currency of the first priced plan. Recurring prices are
+    /// compared as yearly prices. Plans without a price are priced at zero.
+    ///
+    /// Under the priority policy, plans with a lower priority number are preferred. Plans
+    /// without a priority come last. Ties are broken by plan ID.
+    pub fn select(
+        &self,
+        applicable_plans: &super::Plans,
+        parameters: &super::conditions::Parameters,
+        rate_provider: &dyn crate::price::RateProvider,
+    ) -> Result<super::Plans> {
+        if *self == Self::Cumulative {
+            return Ok(applicable_plans.clone());
+        }
+
+        let compulsory_plans = applicable_plans
+            .iter()
+            .filter(|(_id, plan)| plan.r#type == super::PlanType::Compulsory)
+            .map(|(id, plan)| (id.clone(), plan.clone()))
+            .collect::<super::Plans>();
+        let candidate_plans = if compulsory_plans.is_empty() {
+            applicable_plans
+        } else {
+            &compulsory_plans
+        };
+
+        let selected = match self {
+            Self::Priority => candidate_plans
+                .iter()
+                .min_by_key(|(_id, plan)| (plan.priority.is_none(), plan.priority)),
+            _ => {
+                let mut prices = vec![];
+                let mut currency = None;
+                for (id, plan) in candidate_plans {
+                    let quantity = match plan.total_price(&parameters)? {
+                        Some(price) => {
+                            let currency = currency.get_or_insert(price.currency.clone());
+                            let price = price.to(&currency, rate_provider)?;
+                            plan.billing_period.clone().unwrap_or_default().normalize(
+                                &price.quantity,
+                                &super::billing_period::BillingPeriod::Yearly,
+                            )
+                        }
+                        None => crate::price::Quantity::from(0),
+                    };
+                    prices.push((quantity, (id, plan)));
+                }
+                // Stable ordering: the first plan by ID is selected amongst equal prices.
+                let selected = if *self == Self::Cheapest {
+                    prices.into_iter().min_by(|(a, _), (b, _)| a.cmp(&b))
+                } else {
+                    prices.into_iter().rev().max_by(|(a, _), (b, _)| a.cmp(&b))
+                };
+                selected.map(|(_quantity, plan)| plan)
+            }
+        };
+        Ok(selected
+            .map(|(id, plan)| (id.clone(), plan.clone()))
+            .into_iter()
+            .collect())
+    }
+}
+
+impl std::string::ToString for SelectionPolicy {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Cheapest => "cheapest",
+            Self::MostExpensive => "most-expensive",
+            Self::Cumulative => "cumulative",
+            Self::Priority => "priority",
+        }
+        .to_string()
+    }
+}
+
+impl Into<String> for SelectionPolicy {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::convert::TryFrom<&str> for SelectionPolicy {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        for policy in Self::iter() {
+            if policy.to_string().as_str() == value {
+                return Ok(policy);
+            }
+        }
+        let error_message = format!(
+            "Error parsing plan selection policy: {}\nAccepted values:\n{}",
+            value,
+            Self::iter()
+                .map(|policy| policy.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+        Err(anyhow::format_err!(error_message))
+    }
+}
+
+impl std::convert::TryFrom<String> for SelectionPolicy {
+    type Error = anyhow::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl std::str::FromStr for SelectionPolicy {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value)
+    }
+}
+
+#[cfg(test)]
+fn get_plans() -> Result<super::Plans> {
+    let plan = |price: &str, priority: Option<u32>| -> Result<super::Plan> {
+        Ok(super::Plan {
+            r#type: super::PlanType::Compulsory,
+            conditions: Default::default(),
+            price: Some(price.parse()?),
+            unit: None,
+            billing_period: None,
+            priority,
+        })
+    };
+    let mut plans = super::Plans::new();
+    plans.insert("0".to_string(), plan("10 USD", None)?);
+    plans.insert("1".to_string(), plan("30 USD", Some(2))?);
+    plans.insert("2".to_string(), plan("0.001 BTC", Some(1))?);
+    Ok(plans)
+}
+
+#[cfg(test)]
+fn select_ids(policy: &SelectionPolicy) -> Result<Vec<super::Id>> {
+    let plans = get_plans()?;
+    let selected = policy.select(
+        &plans,
+        &Default::default(),
+        &crate::price::FixedRate(20000.into()),
+    )?;
+    Ok(selected.keys().cloned().collect())
+}
+
+#[test]
+fn test_select() -> Result<()> {
+    // 0.001 BTC = 20 USD
+    assert_eq!(select_ids(&SelectionPolicy::Cheapest)?, vec!["0"]);
+    assert_eq!(select_ids(&SelectionPolicy::MostExpensive)?, vec!["1"]);
+    assert_eq!(
+        select_ids(&SelectionPolicy::Cumulative)?,
+        vec!["0", "1", "2"]
+    );
+    assert_eq!(select_ids(&SelectionPolicy::Priority)?, vec!["2"]);
+    Ok(())
+}
+
+#[test]
+fn test_select_keeps_compulsory_plans() -> Result<()> {
+    let mut plans = get_plans()?;
+    plans.insert(
+        "3".to_string(),
+        super::Plan {
+            r#type: super::PlanType::Voluntary,
+            conditions: Default::default(),
+            price: None,
+            unit: None,
+            billing_period: None,
+            priority: Some(0),
+        },
+    );
+    let select_ids = |policy: SelectionPolicy, plans: &super::Plans| -> Result<Vec<super::Id>> {
+        let selected = policy.select(
+            &plans,
+            &Default::default(),
+            &crate::price::FixedRate(20000.into()),
+        )?;
+        Ok(selected.keys().cloned().collect())
+    };
+
+    // The unpriced voluntary plan is neither the cheapest nor preferred over compulsory plans.
+    assert_eq!(select_ids(SelectionPolicy::Cheapest, &plans)?, vec!["0"]);
+    assert_eq!(select_ids(SelectionPolicy::Priority, &plans)?, vec!["2"]);
+    assert_eq!(
+        select_ids(SelectionPolicy::Cumulative, &plans)?,
+        vec!["0", "1", "2", "3"]
+    );
+
+    // Voluntary plans are selected from where no compulsory plan is applicable.
+    plans.retain(|id, _plan| id == "3");
+    assert_eq!(select_ids(SelectionPolicy::Cheapest, &plans)?, vec!["3"]);
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+
+use strum::IntoEnumIterator;
+
+use super::conditions;
+
+pub type Count = u64;
+
+/// Unit of a per-unit plan price.
+#[derive(
+    Debug,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(into = "String", try_from = "String")]
+pub enum Unit {
+    /// Per developer seat.
+    Seat,
+    /// Per deployed instance.
+    Instance,
+    /// Per 1000 end users.
+    EndUsers1000,
+}
+
+impl Unit {
+    /// Number of counted items covered by a single unit.
+    pub fn size(&self) -> Count {
+        match self {
+            Self::Seat | Self::Instance => 1,
+            Self::EndUsers1000 => 1000,
+        }
+    }
+
+    /// Number of units required to cover the given count. Partial units are rounded up.
+    pub fn units(&self, count: &Count) -> Result<Count> {
+        let count = count
+            .checked_add(self.size() - 1)
+            .ok_or(anyhow::format_err!("Unit count too large: {}", count))?;
+        Ok(count / self.size())
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Self::Seat => "number of developer seats",
+            Self::Instance => "number of deployed instances",
+            Self::EndUsers1000 => "number of end users",
+        }
+        .to_string()
+    }
+
+    pub fn metadata(&self) -> Box<dyn conditions::ConditionMetadata> {
+        Box::new(UnitMetadata(self.clone())) as Box<dyn conditions::ConditionMetadata>
+    }
+}
+
+impl std::string::ToString for Unit {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Seat => "seat",
+            Self::Instance => "instance",
+            Self::EndUsers1000 => "1000-end-users",
+        }
+        .to_string()
+    }
+}
+
+impl Into<String> for Unit {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::convert::TryFrom<&str> for Unit {
+    type Error = anyhow::Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        for unit in Self::iter() {
+            if unit.to_string().as_str() == value {
+                return Ok(unit);
+            }
+        }
+        let error_message = format!(
+            "Error parsing plan unit: {}\nAccepted values:\n{}",
+            value,
+            Self::iter()
+                .map(|unit| unit.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+        Err(anyhow::format_err!(error_message))
+    }
+}
+
+impl std::convert::TryFrom<String> for Unit {
+    type Error = anyhow::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl std::str::FromStr for Unit {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value)
+    }
+}
+
+/// Metadata for the user's count of a plan unit. Allows the count to be set interactively
+/// alongside condition parameters.
+#[derive(Debug, Clone)]
+struct UnitMetadata(Unit);
+
+impl conditions::ConditionMetadata for UnitMetadata {
+    fn name(&self) -> String {
+        format!("units.{}", self.0.to_string())
+    }
+
+    fn interactive_set_parameter(&self, parameters: &mut conditions::Parameters) -> Result<()> {
+        let count = dialoguer::Input::<Count>::new()
+            .with_prompt(format!("Enter the {}", self.0.description()))
+            .interact_text()?;
+        parameters.units.insert(self.0.clone(), count);
+        Ok(())
+    }
+
+    fn is_parameter_set(&self, parameters: &conditions::Parameters) -> bool {
+        parameters.units.contains_key(&self.0)
+    }
+}
+
+#[test]
+fn test_units_round_up() -> Result<()> {
+    assert_eq!(Unit::Seat.units(&12)?, 12);
+    assert_eq!(Unit::EndUsers1000.units(&0)?, 0);
+    assert_eq!(Unit::EndUsers1000.units(&1000)?, 1);
+    assert_eq!(Unit::EndUsers1000.units(&1001)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_units_overflow_is_error() {
+    assert!(Unit::EndUsers1000.units(&Count::MAX).is_err());
+    assert!(Unit::Seat.units(&Count::MAX).is_ok());
+}
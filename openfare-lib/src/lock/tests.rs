@@ -18,8 +18,12 @@ fn generate_minimal_valid_lock() -> Value {
                 for_profit: None,
                 expiration: None,
                 employees_count: None,
+                ..Default::default()
             },
             price: None,
+            unit: None,
+            billing_period: None,
+            priority: None,
         },
     );
 
@@ -30,6 +34,7 @@ fn generate_minimal_valid_lock() -> Value {
             profile: Profile {
                 unique_id: Default::default(),
                 payment_methods: Default::default(),
+                price_cap: None,
             },
         },
     );
@@ -50,11 +55,15 @@ fn generate_test_lock() -> Value {
                 for_profit: Some(ForProfit { state: true }),
                 expiration: None,
                 employees_count: None,
+                ..Default::default()
             },
             price: Some(Price {
                 quantity: rust_decimal::Decimal::from(5),
                 currency: Currency::USD,
             }),
+            unit: None,
+            billing_period: None,
+            priority: None,
         },
     );
     lock.plans.insert(
@@ -65,8 +74,12 @@ fn generate_test_lock() -> Value {
                 for_profit: Some(ForProfit { state: false }),
                 expiration: None,
                 employees_count: None,
+                ..Default::default()
             },
             price: None,
+            unit: None,
+            billing_period: None,
+            priority: None,
         },
     );
 
@@ -77,6 +90,7 @@ fn generate_test_lock() -> Value {
             profile: Profile {
                 unique_id: Default::default(),
                 payment_methods: Default::default(),
+                price_cap: None,
             },
         },
     );
@@ -87,6 +101,7 @@ fn generate_test_lock() -> Value {
             profile: Profile {
                 unique_id: Default::default(),
                 payment_methods: Default::default(),
+                price_cap: None,
             },
         },
     );
@@ -147,6 +162,41 @@ fn generate_test_lock_file_with_invalid_plan_condition() -> Value {
         );
     lock
 }
+fn generate_test_lock_file_with_compound_plan_conditions() -> Value {
+    let mut lock = generate_test_lock();
+    lock["plans"]["0"]["conditions"] = json!({
+        "any": [
+            {"for-profit": true},
+            {"not": {"employees-count": "1 <= count < 50"}}
+        ]
+    });
+    lock
+}
+fn generate_test_lock_file_with_empty_compound_plan_condition() -> Value {
+    let mut lock = generate_test_lock();
+    lock["plans"]["0"]["conditions"] = json!({ "any": [] });
+    lock
+}
+fn generate_test_lock_file_with_per_unit_plan_price() -> Value {
+    let mut lock = generate_test_lock();
+    lock["plans"]["0"]["unit"] = json!("seat");
+    lock
+}
+fn generate_test_lock_file_with_unknown_plan_unit() -> Value {
+    let mut lock = generate_test_lock();
+    lock["plans"]["0"]["unit"] = json!("fortnight");
+    lock
+}
+fn generate_test_lock_file_with_recurring_plan_price() -> Value {
+    let mut lock = generate_test_lock();
+    lock["plans"]["0"]["billing-period"] = json!("monthly");
+    lock
+}
+fn generate_test_lock_file_with_unknown_billing_period() -> Value {
+    let mut lock = generate_test_lock();
+    lock["plans"]["0"]["billing-period"] = json!("daily");
+    lock
+}
 fn generate_test_lock_file_with_more_share_labels_than_payees() -> Value {
     let mut lock = generate_test_lock();
     lock["shares"]
@@ -223,6 +273,96 @@ fn test_valid_payment_conditions_only() {
     .is_err());
 }
 #[test]
+fn test_compound_plan_conditions_are_valid() {
+    assert!(validate_lock_file_json_and_print_errs(
+        generate_test_lock_file_with_compound_plan_conditions()
+    )
+    .is_ok());
+}
+#[test]
+fn test_compound_plan_condition_cannot_be_empty() {
+    assert!(validate_lock_file_json_and_print_errs(
+        generate_test_lock_file_with_empty_compound_plan_condition()
+    )
+    .is_err());
+}
+#[test]
+fn test_per_unit_plan_price_is_valid() {
+    assert!(validate_lock_file_json_and_print_errs(
+        generate_test_lock_file_with_per_unit_plan_price()
+    )
+    .is_ok());
+}
+#[test]
+fn test_unknown_plan_unit_not_valid() {
+    assert!(validate_lock_file_json_and_print_errs(
+        generate_test_lock_file_with_unknown_plan_unit()
+    )
+    .is_err());
+}
+#[test]
+fn test_per_unit_plan_total_price() -> Result<()> {
+    let lock: Lock = serde_json::from_value(generate_test_lock_file_with_per_unit_plan_price())?;
+    let plan = &lock.plans["0"];
+
+    let mut parameters = plan::conditions::Parameters::default();
+    assert!(plan.total_price(&parameters).is_err());
+
+    parameters.units.insert(plan::unit::Unit::Seat, 12);
+    let price = plan.total_price(&parameters)?.unwrap();
+    assert_eq!(price.quantity, rust_decimal::Decimal::from(60));
+    Ok(())
+}
+#[test]
+fn test_per_unit_plan_total_price_overflow_is_error() -> Result<()> {
+    let lock: Lock = serde_json::from_value(generate_test_lock_file_with_per_unit_plan_price())?;
+    let mut plan = lock.plans["0"].clone();
+    if let Some(price) = plan.price.as_mut() {
+        price.quantity = rust_decimal::Decimal::from(u64::MAX);
+    }
+
+    let mut parameters = plan::conditions::Parameters::default();
+    parameters.units.insert(plan::unit::Unit::Seat, u64::MAX);
+    assert!(plan.total_price(&parameters).is_err());
+    Ok(())
+}
+#[test]
+fn test_per_unit_plan_with_unset_count_is_undecidable() -> Result<()> {
+    let lock: Lock = serde_json::from_value(generate_test_lock_file_with_per_unit_plan_price())?;
+    let mut parameters = plan::conditions::Parameters::default();
+    parameters.for_profit = Some(true);
+
+    let filtered_plans = plan::filter_applicable(&lock.plans, &parameters)?;
+    assert!(!filtered_plans.applicable.contains_key("0"));
+    assert_eq!(
+        filtered_plans
+            .undecidable
+            .get("0")
+            .map(|reason| reason.as_str()),
+        Some("unit count for seat not set")
+    );
+
+    parameters.units.insert(plan::unit::Unit::Seat, 12);
+    let filtered_plans = plan::filter_applicable(&lock.plans, &parameters)?;
+    assert!(filtered_plans.applicable.contains_key("0"));
+    assert!(filtered_plans.undecidable.is_empty());
+    Ok(())
+}
+#[test]
+fn test_recurring_plan_price_is_valid() {
+    assert!(validate_lock_file_json_and_print_errs(
+        generate_test_lock_file_with_recurring_plan_price()
+    )
+    .is_ok());
+}
+#[test]
+fn test_unknown_billing_period_not_valid() {
+    assert!(validate_lock_file_json_and_print_errs(
+        generate_test_lock_file_with_unknown_billing_period()
+    )
+    .is_err());
+}
+#[test]
 fn test_shares_cannot_be_negative() {
     assert!(
         validate_lock_file_json_and_print_errs(generate_test_lock_file_with_negative_shares())
@@ -245,3 +385,109 @@ fn test_share_labels_must_match_payees() {
     )
     .is_err());
 }
+#[test]
+fn test_payee_price_cap_applied_across_packages() -> Result<()> {
+    let mut lock: Lock = serde_json::from_value(generate_test_lock())?;
+    for (_label, payee) in lock.payees.iter_mut() {
+        payee.profile.unique_id = uuid::Uuid::new_v4();
+    }
+    lock.payees.get_mut("steve").unwrap().profile.price_cap = Some(crate::profile::PriceCap {
+        price: "100 USD".parse()?,
+        billing_period: None,
+    });
+    let mut uncapped_payees = lock.payees.clone();
+    uncapped_payees.remove("steve");
+
+    let price: Price = "120 USD".parse()?;
+    let plan_prices = vec![(price.clone(), plan::billing_period::BillingPeriod::OneOff)];
+    let package_prices = vec![
+        (
+            lock.payees.clone(),
+            lock.shares.clone(),
+            price.clone(),
+            plan_prices.clone(),
+        ),
+        (
+            lock.payees.clone(),
+            lock.shares.clone(),
+            price.clone(),
+            plan_prices.clone(),
+        ),
+        (uncapped_payees, None, price.clone(), plan_prices.clone()),
+    ];
+    let capped_prices =
+        payee::apply_price_caps(&package_prices, &crate::price::FixedRate(30000.into()))?;
+
+    // Steve receives 1000/1200 of each package price (100 USD). Steve's total across packages
+    // (200 USD) is capped at 100 USD.
+    assert_eq!(capped_prices[0], "70 USD".parse()?);
+    assert_eq!(capped_prices[1], "70 USD".parse()?);
+    assert_eq!(capped_prices[2], price);
+    Ok(())
+}
+#[test]
+fn test_payee_price_cap_converted_and_normalized() -> Result<()> {
+    let mut lock: Lock = serde_json::from_value(generate_test_lock())?;
+    for (_label, payee) in lock.payees.iter_mut() {
+        payee.profile.unique_id = uuid::Uuid::new_v4();
+    }
+    // 1200 USD per year = 1000 EUR per year.
+    lock.payees.get_mut("steve").unwrap().profile.price_cap = Some(crate::profile::PriceCap {
+        price: "1200 USD".parse()?,
+        billing_period: Some(plan::billing_period::BillingPeriod::Yearly),
+    });
+    let rates: crate::price::StaticRates = serde_json::from_value(json!({
+        "USD": "36000",
+        "EUR": "30000"
+    }))?;
+
+    let package_prices = vec![(
+        lock.payees.clone(),
+        lock.shares.clone(),
+        "120 EUR".parse()?,
+        vec![(
+            "120 EUR".parse()?,
+            plan::billing_period::BillingPeriod::Monthly,
+        )],
+    )];
+    let capped_prices = payee::apply_price_caps(&package_prices, &rates)?;
+
+    // Steve receives 100 EUR of 120 EUR per month (1200 EUR per year). Steve's portion is
+    // reduced to 1000/1200 of 100 EUR, rounded up.
+    assert_eq!(capped_prices[0], "103.34 EUR".parse()?);
+    Ok(())
+}
+#[test]
+fn test_payee_price_cap_normalizes_each_plan_price() -> Result<()> {
+    let mut lock: Lock = serde_json::from_value(generate_test_lock())?;
+    lock.payees.retain(|label, _payee| label == "steve");
+    let steve = lock.payees.get_mut("steve").unwrap();
+    steve.profile.unique_id = uuid::Uuid::new_v4();
+    steve.profile.price_cap = Some(crate::profile::PriceCap {
+        price: "110 USD".parse()?,
+        billing_period: Some(plan::billing_period::BillingPeriod::Yearly),
+    });
+
+    // Plans paid at differing billing periods. The package price is their plain sum.
+    let package_prices = vec![(
+        lock.payees.clone(),
+        None,
+        "110 USD".parse()?,
+        vec![
+            (
+                "10 USD".parse()?,
+                plan::billing_period::BillingPeriod::Monthly,
+            ),
+            (
+                "100 USD".parse()?,
+                plan::billing_period::BillingPeriod::Yearly,
+            ),
+        ],
+    )];
+    let capped_prices =
+        payee::apply_price_caps(&package_prices, &crate::price::FixedRate(30000.into()))?;
+
+    // Plans total 220 USD per year, which is capped at 110 USD per year.
+    assert_eq!(capped_prices[0], "55 USD".parse()?);
+    Ok(())
+}
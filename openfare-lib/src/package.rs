@@ -9,6 +9,68 @@ pub struct Package {
 }
 
 pub type DependenciesLocks = std::collections::BTreeMap<Package, Option<lock::Lock>>;
+pub type DependenciesKinds = std::collections::BTreeMap<Package, DependencyKind>;
+
+/// Dependency tree edges. Maps a package to the packages which it directly depends on.
+pub type DependenciesEdges =
+    std::collections::BTreeMap<Package, std::collections::BTreeSet<Package>>;
+
+/// How a package is depended upon.
+#[derive(
+    Debug,
+    Clone,
+    Hash,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    Runtime,
+    Dev,
+    Build,
+    Optional,
+}
+
+impl std::default::Default for DependencyKind {
+    fn default() -> Self {
+        Self::Runtime
+    }
+}
+
+impl std::fmt::Display for DependencyKind {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Self::Runtime => "runtime",
+            Self::Dev => "dev",
+            Self::Build => "build",
+            Self::Optional => "optional",
+        };
+        write!(formatter, "{}", kind)
+    }
+}
+
+impl std::str::FromStr for DependencyKind {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "runtime" => Self::Runtime,
+            "dev" => Self::Dev,
+            "build" => Self::Build,
+            "optional" => Self::Optional,
+            _ => {
+                return Err(anyhow::format_err!(
+                    "Unknown dependency kind: {}. Supported values: [runtime|dev|build|optional].",
+                    value
+                ));
+            }
+        })
+    }
+}
 
 // TODO: Add 'Result' version of PackageLocks for extension lock file errors.
 
@@ -17,6 +79,16 @@ pub struct PackageLocks {
     pub primary_package: Option<Package>,
     pub primary_package_lock: Option<lock::Lock>,
     pub dependencies_locks: DependenciesLocks,
+
+    /// Dependency kinds as reported by the extension. Dependencies are runtime dependencies
+    /// unless stated otherwise.
+    #[serde(default)]
+    pub dependencies_kinds: DependenciesKinds,
+
+    /// Dependency tree edges as reported by the extension. All dependencies are direct
+    /// dependencies of the primary package if no edges are given.
+    #[serde(default)]
+    pub dependencies_edges: DependenciesEdges,
 }
 
 impl PackageLocks {
@@ -30,7 +102,7 @@ impl PackageLocks {
 
         let mut handle_lock = |lock: &lock::Lock| {
             for (_id, plan) in &lock.plans {
-                for metadata in plan.conditions.metadata() {
+                for metadata in plan.metadata() {
                     if !result.iter().any(
                         |m: &Box<dyn lock::plan::conditions::ConditionMetadata>| {
                             *m.name() == *metadata.name()
@@ -54,6 +126,66 @@ impl PackageLocks {
         result
     }
 
+    /// Returns the kind of the given dependency.
+    pub fn dependency_kind(&self, package: &Package) -> DependencyKind {
+        self.dependencies_kinds
+            .get(package)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the direct dependencies of the primary package.
+    ///
+    /// Uses the primary package's edges if given. Otherwise, direct dependencies are those which
+    /// are not depended upon by any other retained dependency.
+    pub fn direct_dependencies(&self) -> Vec<&Package> {
+        if let Some(primary_package) = &self.primary_package {
+            if self.dependencies_edges.contains_key(primary_package) {
+                return self.child_dependencies(primary_package);
+            }
+        }
+        let children = self
+            .dependencies_edges
+            .iter()
+            .filter(|(parent, _children)| self.dependencies_locks.contains_key(parent))
+            .flat_map(|(_parent, children)| children)
+            .collect::<std::collections::BTreeSet<_>>();
+        self.dependencies_locks
+            .keys()
+            .filter(|package| !children.contains(package))
+            .collect()
+    }
+
+    /// Returns the dependencies which the given package directly depends on.
+    pub fn child_dependencies(&self, package: &Package) -> Vec<&Package> {
+        match self.dependencies_edges.get(package) {
+            Some(children) => children
+                .iter()
+                .filter_map(|child| {
+                    self.dependencies_locks
+                        .get_key_value(child)
+                        .map(|(child, _lock)| child)
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Filter for dependencies of the given kinds. All dependencies retained if no kinds given.
+    pub fn filter_dependencies_kinds(&self, kinds: &Vec<DependencyKind>) -> Self {
+        if kinds.is_empty() {
+            return self.clone();
+        }
+        let mut result = self.clone();
+        result
+            .dependencies_locks
+            .retain(|package, _lock| kinds.contains(&self.dependency_kind(package)));
+        result
+            .dependencies_kinds
+            .retain(|package, _kind| kinds.contains(&self.dependency_kind(package)));
+        result
+    }
+
     /// Filter for valid dependencies locks.
     pub fn filter_valid_dependencies_locks(&self) -> Self {
         let mut result = self.clone();
@@ -70,3 +202,38 @@ impl PackageLocks {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str) -> Package {
+        Package {
+            registry: "registry.example.com".to_string(),
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_direct_dependencies() {
+        let mut package_locks = PackageLocks::default();
+        for name in ["a", "b", "c"] {
+            package_locks.dependencies_locks.insert(package(name), None);
+        }
+        assert_eq!(package_locks.direct_dependencies().len(), 3);
+
+        package_locks.dependencies_edges.insert(
+            package("a"),
+            vec![package("c"), package("missing")].into_iter().collect(),
+        );
+        assert_eq!(
+            package_locks.direct_dependencies(),
+            vec![&package("a"), &package("b")]
+        );
+        assert_eq!(
+            package_locks.child_dependencies(&package("a")),
+            vec![&package("c")]
+        );
+    }
+}
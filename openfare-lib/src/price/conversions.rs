@@ -1,95 +1,168 @@
-use super::{Currency, Price};
-use anyhow::Result;
-
-pub fn one_btc_in_usd() -> Result<rust_decimal::Decimal> {
-    let client = reqwest::blocking::Client::new();
-    let response = client
-        .get("http://rate.sx/1BTC")
-        .header(reqwest::header::USER_AGENT, crate::HTTP_USER_AGENT)
-        .send()?
-        .text()?;
-    let response = response.replace("\n", "");
-    let one_btc_in_usd = rust_decimal::Decimal::from_str_exact(&response)?;
-    Ok(one_btc_in_usd)
-}
+use super::{Currency, Price, RateProvider};
+use anyhow::{format_err, Result};
 
-// To BTC.
+use strum::IntoEnumIterator;
 
-pub fn usd_to_btc(usd_price: &Price) -> Result<Price> {
-    let target_currency = Currency::BTC;
-    let one_btc_in_usd = one_btc_in_usd()?;
-    let one_usd_in_btc = rust_decimal::Decimal::from(1) / one_btc_in_usd;
-    let quantity = (usd_price.quantity * one_usd_in_btc).round_dp_with_strategy(
-        target_currency.decimal_points(),
-        rust_decimal::prelude::RoundingStrategy::AwayFromZero,
-    );
+/// Exchange rate between two currencies.
+#[derive(Debug, Clone)]
+enum Rate {
+    /// Fixed number of target currency units per source currency unit.
+    Fixed(rust_decimal::Decimal),
+    /// Market price of one BTC in the given currency.
+    BtcMarket(Currency),
+    /// Inverse of the market price of one BTC in the given currency.
+    InverseBtcMarket(Currency),
+}
 
-    Ok(Price {
-        quantity,
-        currency: target_currency,
-    })
+impl Rate {
+    /// Returns the number of target currency units per source currency unit.
+    fn get(&self, rate_provider: &dyn RateProvider) -> Result<rust_decimal::Decimal> {
+        Ok(match self {
+            Self::Fixed(rate) => rate.clone(),
+            Self::BtcMarket(currency) => market_rate(&currency, rate_provider)?,
+            Self::InverseBtcMarket(currency) => {
+                rust_decimal::Decimal::from(1) / market_rate(&currency, rate_provider)?
+            }
+        })
+    }
 }
 
-pub fn sats_to_btc(sats_price: &Price) -> Result<Price> {
-    let target_currency = Currency::BTC;
-    let quantity = (sats_price.quantity * rust_decimal::Decimal::from_str_exact("0.00000001")?)
-        .round_dp_with_strategy(
-            target_currency.decimal_points(),
-            rust_decimal::prelude::RoundingStrategy::AwayFromZero,
-        );
-    Ok(Price {
-        quantity,
-        currency: target_currency,
-    })
+/// Returns the price of one BTC in the given currency. Errors if the rate is not positive.
+fn market_rate(
+    currency: &Currency,
+    rate_provider: &dyn RateProvider,
+) -> Result<rust_decimal::Decimal> {
+    let rate = rate_provider.one_btc_in(&currency)?;
+    super::rates::check_rate(&currency, &rate)?;
+    Ok(rate)
 }
 
-// To sats.
+/// A directed edge in the currency conversion graph.
+#[derive(Debug, Clone)]
+struct Edge {
+    from: Currency,
+    to: Currency,
+    rate: Rate,
+}
 
-pub fn usd_to_sats(usd_price: &Price) -> Result<Price> {
-    let btc_price = usd_to_btc(usd_price)?;
-    btc_to_sats(&btc_price)
+/// Returns the edges of the currency conversion graph.
+///
+/// MSAT, SATS and BTC convert at fixed rates. Fiat and other crypto currencies convert to and
+/// from BTC at the market rate. Conversions between them go via BTC.
+fn edges() -> Vec<Edge> {
+    let sats_per_btc = rust_decimal::Decimal::from(100000000 as i64);
+    let msat_per_sat = rust_decimal::Decimal::from(1000 as i64);
+    let mut edges = vec![
+        Edge {
+            from: Currency::BTC,
+            to: Currency::SATS,
+            rate: Rate::Fixed(sats_per_btc),
+        },
+        Edge {
+            from: Currency::SATS,
+            to: Currency::BTC,
+            rate: Rate::Fixed(rust_decimal::Decimal::from(1) / sats_per_btc),
+        },
+        Edge {
+            from: Currency::SATS,
+            to: Currency::MSAT,
+            rate: Rate::Fixed(msat_per_sat),
+        },
+        Edge {
+            from: Currency::MSAT,
+            to: Currency::SATS,
+            rate: Rate::Fixed(rust_decimal::Decimal::from(1) / msat_per_sat),
+        },
+    ];
+    for currency in Currency::iter().filter(|currency| !currency.is_bitcoin()) {
+        edges.push(Edge {
+            from: Currency::BTC,
+            to: currency.clone(),
+            rate: Rate::BtcMarket(currency.clone()),
+        });
+        edges.push(Edge {
+            from: currency.clone(),
+            to: Currency::BTC,
+            rate: Rate::InverseBtcMarket(currency.clone()),
+        });
+    }
+    edges
 }
 
-pub fn btc_to_sats(btc_price: &Price) -> Result<Price> {
-    let target_currency = Currency::SATS;
-    let quantity = (btc_price.quantity * rust_decimal::Decimal::from(100000000 as i64))
-        .round_dp_with_strategy(
-            target_currency.decimal_points(),
-            rust_decimal::prelude::RoundingStrategy::AwayFromZero,
-        );
-    Ok(Price {
-        quantity,
-        currency: target_currency,
-    })
+/// Returns the shortest path of edges between two currencies.
+fn find_path(from: &Currency, to: &Currency) -> Result<Vec<Edge>> {
+    let edges = edges();
+    let mut previous = std::collections::BTreeMap::<Currency, Edge>::new();
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(from.clone());
+
+    while let Some(currency) = queue.pop_front() {
+        if &currency == to {
+            break;
+        }
+        for edge in edges.iter().filter(|edge| edge.from == currency) {
+            if &edge.to == from || previous.contains_key(&edge.to) {
+                continue;
+            }
+            previous.insert(edge.to.clone(), edge.clone());
+            queue.push_back(edge.to.clone());
+        }
+    }
+
+    let mut path = vec![];
+    let mut currency = to.clone();
+    while &currency != from {
+        let edge = previous.get(&currency).ok_or(format_err!(
+            "Failed to find conversion from {} to {}.",
+            from,
+            to
+        ))?;
+        currency = edge.from.clone();
+        path.push(edge.clone());
+    }
+    path.reverse();
+    Ok(path)
 }
 
-// To USD.
+/// Converts price into the given currency using market rates from the given provider.
+pub fn convert(
+    price: &Price,
+    currency: &Currency,
+    rate_provider: &dyn RateProvider,
+) -> Result<Price> {
+    if &price.currency == currency {
+        return Ok(price.clone());
+    }
+    if price.quantity.is_zero() {
+        return Ok(Price {
+            quantity: price.quantity,
+            currency: currency.clone(),
+        });
+    }
 
-pub fn btc_to_usd(btc_price: &Price) -> Result<Price> {
-    let one_btc_in_usd = one_btc_in_usd()?;
-    let quantity = (one_btc_in_usd * btc_price.quantity).round_dp_with_strategy(
-        Currency::USD.decimal_points(),
+    let mut quantity = price.quantity;
+    for edge in find_path(&price.currency, &currency)? {
+        quantity = quantity * edge.rate.get(rate_provider)?;
+    }
+    let quantity = quantity.round_dp_with_strategy(
+        currency.decimal_points(),
         rust_decimal::prelude::RoundingStrategy::AwayFromZero,
     );
     Ok(Price {
         quantity,
-        currency: Currency::USD,
+        currency: currency.clone(),
     })
 }
 
-pub fn sats_to_usd(sats_price: &Price) -> Result<Price> {
-    let btc_price = sats_to_btc(&sats_price)?;
-    btc_to_usd(&btc_price)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::price::{FixedRate, StaticRates};
 
     #[test]
     fn test_sats_to_btc() -> anyhow::Result<()> {
         let result = Price::try_from("50   sats")?;
-        let result = sats_to_btc(&result)?;
+        let result = convert(&result, &Currency::BTC, &FixedRate(1.into()))?;
         let expected = Price::try_from("0.00000050 btc")?;
         assert!(result == expected);
         Ok(())
@@ -98,9 +171,82 @@ mod tests {
     #[test]
     fn test_btc_to_sats() -> anyhow::Result<()> {
         let result = Price::try_from("2 BTC")?;
-        let result = btc_to_sats(&result)?;
+        let result = convert(&result, &Currency::SATS, &FixedRate(1.into()))?;
         let expected = Price::try_from("200000000SATS")?;
         assert!(result == expected);
         Ok(())
     }
+
+    #[test]
+    fn test_to_msat() -> anyhow::Result<()> {
+        let result = convert(
+            &Price::try_from("1 sats")?,
+            &Currency::MSAT,
+            &FixedRate(1.into()),
+        )?;
+        assert_eq!(result, Price::try_from("1000 msat")?);
+
+        let result = convert(
+            &Price::try_from("0.5 usd")?,
+            &Currency::MSAT,
+            &FixedRate(30000.into()),
+        )?;
+        assert_eq!(result, Price::try_from("1666667 msat")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fiat_to_fiat_path_via_btc() -> anyhow::Result<()> {
+        let path = find_path(&Currency::EUR, &Currency::GBP)?
+            .iter()
+            .map(|edge| edge.to.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(path, vec![Currency::BTC, Currency::GBP]);
+
+        let path = find_path(&Currency::SATS, &Currency::USD)?
+            .iter()
+            .map(|edge| edge.to.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(path, vec![Currency::BTC, Currency::USD]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_crypto_to_fiat() -> anyhow::Result<()> {
+        let rates: StaticRates = serde_json::from_value(serde_json::json!({
+            "USD": "40000",
+            "USDC": "40020",
+            "ETH": "16"
+        }))?;
+        let result = convert(&Price::try_from("20.01 USDC")?, &Currency::USD, &rates)?;
+        assert_eq!(result, Price::try_from("20.00 USD")?);
+
+        let result = convert(&Price::try_from("0.1 ETH")?, &Currency::USD, &rates)?;
+        assert_eq!(result, Price::try_from("250.00 USD")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fiat_to_fiat_uses_provider_rates() -> anyhow::Result<()> {
+        let rates: StaticRates = serde_json::from_value(serde_json::json!({
+            "USD": "40000",
+            "EUR": "32000"
+        }))?;
+        let result = convert(&Price::try_from("10 EUR")?, &Currency::USD, &rates)?;
+        assert_eq!(result, Price::try_from("12.50 USD")?);
+
+        assert!(convert(&Price::try_from("10 GBP")?, &Currency::USD, &rates).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_rate_is_error() -> anyhow::Result<()> {
+        let result = convert(
+            &Price::try_from("10 USD")?,
+            &Currency::SATS,
+            &FixedRate(0.into()),
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
 }
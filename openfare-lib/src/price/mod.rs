@@ -1,35 +1,123 @@
 use anyhow::{format_err, Result};
 use std::str::FromStr;
+use strum::IntoEnumIterator;
 
 mod conversions;
+mod rates;
+
+pub use rates::{FixedRate, RateProvider, RateSx, Rates, Snapshot, StaticRates};
 
 #[derive(
-    Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
+    Debug,
+    Clone,
+    Hash,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
 )]
 pub enum Currency {
     USD,
+    EUR,
+    GBP,
+    JPY,
+    CHF,
+    CAD,
+    AUD,
+    CNY,
+    INR,
+    SEK,
+    NOK,
+    DKK,
+    PLN,
 
     BTC,
     SATS,
+    /// Millisatoshi. Lightning network payments are made in whole msat.
+    MSAT,
+
+    ETH,
+    USDC,
+    USDT,
 }
 
 impl Currency {
+    /// Number of decimal places in the currency's minor unit (ISO 4217).
     pub fn decimal_points(&self) -> u32 {
         match self {
-            Self::USD => 2,
+            Self::JPY => 0,
             Self::BTC => 8,
             Self::SATS => 0,
+            Self::MSAT => 0,
+            Self::ETH => 18,
+            Self::USDC | Self::USDT => 6,
+            _ => 2,
+        }
+    }
+
+    /// Number of decimal points shown when displaying a price. Less than the stored precision
+    /// where the smallest unit is too small to be meaningful to a reader.
+    pub fn display_decimal_points(&self) -> u32 {
+        match self {
+            Self::ETH => 6,
+            _ => self.decimal_points(),
         }
     }
 
     pub fn to_symbol(&self) -> String {
         match self {
             Self::USD => "$",
+            Self::EUR => "€",
+            Self::GBP => "£",
+            Self::JPY => "¥",
+            Self::CHF => "CHF",
+            Self::CAD => "CA$",
+            Self::AUD => "A$",
+            Self::CNY => "CN¥",
+            Self::INR => "₹",
+            Self::SEK => "SEK",
+            Self::NOK => "NOK",
+            Self::DKK => "DKK",
+            Self::PLN => "PLN",
             Self::BTC => "₿",
             Self::SATS => "sats",
+            Self::MSAT => "msat",
+            Self::ETH => "Ξ",
+            Self::USDC => "USDC",
+            Self::USDT => "USDT",
         }
         .to_string()
     }
+
+    /// Returns true if the currency symbol precedes the quantity.
+    fn is_symbol_prefix(&self) -> bool {
+        match self {
+            Self::CHF | Self::SEK | Self::NOK | Self::DKK | Self::PLN | Self::SATS | Self::MSAT => {
+                false
+            }
+            Self::USDC | Self::USDT => false,
+            _ => true,
+        }
+    }
+
+    pub fn is_fiat(&self) -> bool {
+        match self {
+            Self::BTC | Self::SATS | Self::MSAT => false,
+            Self::ETH | Self::USDC | Self::USDT => false,
+            _ => true,
+        }
+    }
+
+    /// Returns true if the currency is a denomination of BTC.
+    pub fn is_bitcoin(&self) -> bool {
+        match self {
+            Self::BTC | Self::SATS | Self::MSAT => true,
+            _ => false,
+        }
+    }
 }
 
 impl std::default::Default for Currency {
@@ -41,15 +129,12 @@ impl std::default::Default for Currency {
 impl std::convert::TryFrom<&str> for Currency {
     type Error = anyhow::Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let value = value.to_string().to_uppercase();
-        Ok(match value.to_lowercase().as_str() {
-            "usd" => Self::USD,
-            "btc" => Self::BTC,
-            "sats" => Self::SATS,
-            _ => {
-                return Err(format_err!("Unknown currency: {}", value));
+        for currency in Self::iter() {
+            if currency.to_string().to_lowercase() == value.to_lowercase() {
+                return Ok(currency);
             }
-        })
+        }
+        Err(format_err!("Unknown currency: {}", value.to_uppercase()))
     }
 }
 
@@ -57,8 +142,24 @@ impl std::fmt::Display for Currency {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let currency = match self {
             Self::USD => "USD",
+            Self::EUR => "EUR",
+            Self::GBP => "GBP",
+            Self::JPY => "JPY",
+            Self::CHF => "CHF",
+            Self::CAD => "CAD",
+            Self::AUD => "AUD",
+            Self::CNY => "CNY",
+            Self::INR => "INR",
+            Self::SEK => "SEK",
+            Self::NOK => "NOK",
+            Self::DKK => "DKK",
+            Self::PLN => "PLN",
             Self::BTC => "BTC",
             Self::SATS => "SATS",
+            Self::MSAT => "MSAT",
+            Self::ETH => "ETH",
+            Self::USDC => "USDC",
+            Self::USDT => "USDT",
         };
         write!(formatter, "{}", currency)
     }
@@ -74,69 +175,96 @@ pub struct Price {
 
 impl Price {
     pub fn to_symbolic(&self) -> String {
-        match self.currency {
-            Currency::USD | Currency::BTC => {
-                format!(
-                    "{currency}{:.1$}",
-                    self.quantity,
-                    self.currency.decimal_points() as usize,
-                    currency = self.currency.to_symbol(),
-                )
-            }
-            Currency::SATS => {
-                format!(
-                    "{:.1$}{currency}",
-                    self.quantity,
-                    self.currency.decimal_points() as usize,
-                    currency = self.currency.to_symbol(),
-                )
-            }
+        let decimal_points = self.currency.display_decimal_points();
+        let quantity = format!(
+            "{:.1$}",
+            self.quantity.round_dp(decimal_points),
+            decimal_points as usize
+        );
+        let symbol = self.currency.to_symbol();
+        if self.currency.is_symbol_prefix() {
+            format!("{}{}", symbol, quantity)
+        } else if self.currency == Currency::SATS || self.currency == Currency::MSAT {
+            format!("{}{}", quantity, symbol)
+        } else {
+            format!("{} {}", quantity, symbol)
         }
     }
 
-    pub fn to_btc(&self) -> Result<Price> {
-        match &self.currency {
-            Currency::USD => conversions::usd_to_btc(&self),
-            Currency::BTC => Ok(self.clone()),
-            Currency::SATS => conversions::sats_to_btc(&self),
-        }
+    /// Converts price into the given currency.
+    pub fn to(&self, currency: &Currency, rate_provider: &dyn RateProvider) -> Result<Price> {
+        conversions::convert(&self, &currency, rate_provider)
     }
 
-    pub fn to_sats(&self) -> Result<Price> {
-        match &self.currency {
-            Currency::USD => conversions::usd_to_sats(&self),
-            Currency::BTC => conversions::btc_to_sats(&self),
-            Currency::SATS => Ok(self.clone()),
-        }
+    pub fn to_btc(&self, rate_provider: &dyn RateProvider) -> Result<Price> {
+        self.to(&Currency::BTC, rate_provider)
     }
 
-    pub fn to_usd(&self) -> Result<Price> {
-        match &self.currency {
-            Currency::USD => Ok(self.clone()),
-            Currency::BTC => conversions::btc_to_usd(&self),
-            Currency::SATS => conversions::sats_to_usd(&self),
+    pub fn to_sats(&self, rate_provider: &dyn RateProvider) -> Result<Price> {
+        self.to(&Currency::SATS, rate_provider)
+    }
+
+    pub fn to_msat(&self, rate_provider: &dyn RateProvider) -> Result<Price> {
+        self.to(&Currency::MSAT, rate_provider)
+    }
+
+    pub fn to_usd(&self, rate_provider: &dyn RateProvider) -> Result<Price> {
+        self.to(&Currency::USD, rate_provider)
+    }
+}
+
+impl std::ops::Add for Price {
+    type Output = Result<Price>;
+
+    /// Adds prices of the same currency. Errors if currencies differ.
+    fn add(self, other: Self) -> Self::Output {
+        if self.currency != other.currency {
+            return Err(format_err!(
+                "Attempting to add prices of different currencies: {} and {}",
+                self,
+                other
+            ));
         }
+        Ok(Self {
+            quantity: self.quantity + other.quantity,
+            currency: self.currency,
+        })
     }
 }
 
-impl std::iter::Sum for Price {
-    fn sum<I>(iter: I) -> Self
+/// Sums prices of the same currency. Errors if currencies differ.
+impl std::iter::Sum<Price> for Result<Price> {
+    fn sum<I>(mut iter: I) -> Self
     where
-        I: Iterator<Item = Self>,
+        I: Iterator<Item = Price>,
     {
-        let mut currency = None;
-        let mut quantity = Quantity::from(0 as i64);
-        for price in iter {
-            quantity += price.quantity;
-            currency = Some(price.currency);
-        }
-        Self {
-            quantity,
-            currency: currency.unwrap_or_default(),
-        }
+        let first = match iter.next() {
+            Some(price) => price,
+            None => return Ok(Price::default()),
+        };
+        iter.fold(Ok(first), |total, price| total? + price)
     }
 }
 
+/// Converts prices into the given currency and sums them.
+pub fn sum_in<'a, I>(
+    prices: I,
+    currency: &Currency,
+    rate_provider: &dyn RateProvider,
+) -> Result<Price>
+where
+    I: Iterator<Item = &'a Price>,
+{
+    let mut total = Price {
+        quantity: Quantity::from(0),
+        currency: currency.clone(),
+    };
+    for price in prices {
+        total = (total + price.to(&currency, rate_provider)?)?;
+    }
+    Ok(total)
+}
+
 impl std::convert::TryFrom<&str> for Price {
     type Error = anyhow::Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
@@ -164,11 +292,12 @@ impl std::str::FromStr for Price {
 
 impl std::fmt::Display for Price {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let decimal_points = self.currency.display_decimal_points();
         write!(
             formatter,
             "{:.1$} {currency}",
-            self.quantity,
-            self.currency.decimal_points() as usize,
+            self.quantity.round_dp(decimal_points),
+            decimal_points as usize,
             currency = self.currency.to_string()
         )
     }
@@ -259,14 +388,7 @@ fn parse_currency(regex_capture: &Option<regex::Match>) -> Result<Currency> {
     let error_message = "Failed to parse currency";
     let currency = regex_capture.ok_or(format_err!(error_message))?.as_str();
 
-    let currency = match currency.to_lowercase().as_str() {
-        "usd" => Currency::USD,
-        "btc" => Currency::BTC,
-        "sats" => Currency::SATS,
-        _ => {
-            return Err(format_err!(error_message));
-        }
-    };
+    let currency = Currency::try_from(currency).map_err(|_| format_err!(error_message))?;
     Ok(currency)
 }
 
@@ -292,6 +414,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_to_symbolic_crypto() -> anyhow::Result<()> {
+        assert_eq!(
+            Price::try_from("12.5 usdc")?.to_symbolic(),
+            "12.500000 USDC"
+        );
+        assert_eq!(Price::try_from("0.1234567 ETH")?.to_symbolic(), "Ξ0.123457");
+        assert_eq!(
+            Price::try_from("0.1234567 ETH")?.to_string(),
+            "0.123457 ETH"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_eth_keeps_precision() -> anyhow::Result<()> {
+        let price = Price::try_from("0.1234567 ETH")?;
+        assert_eq!(
+            serde_json::to_string(&price)?,
+            "\"0.123456700000000000 ETH\""
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_serialize_usd() -> anyhow::Result<()> {
         #[derive(serde::Serialize)]
@@ -345,10 +491,44 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_str_fiat_price_correctly_parsed() -> anyhow::Result<()> {
+        let result = Price::try_from("50.5 eur")?;
+        assert_eq!(result.currency, Currency::EUR);
+        assert_eq!(result.to_symbolic(), "€50.50".to_string());
+
+        let result = Price::try_from("1200 JPY")?;
+        assert_eq!(result.to_symbolic(), "¥1200".to_string());
+
+        let result = Price::try_from("20 chf")?;
+        assert_eq!(result.to_symbolic(), "20.00 CHF".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sum_mixed_currencies_errors() -> anyhow::Result<()> {
+        let prices = vec![Price::try_from("10 usd")?, Price::try_from("5 usd")?];
+        let total: Result<Price> = prices.into_iter().sum();
+        assert_eq!(total?, Price::try_from("15 usd")?);
+
+        let prices = vec![Price::try_from("10 usd")?, Price::try_from("1 btc")?];
+        let total: Result<Price> = prices.into_iter().sum();
+        assert!(total.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sum_in_converts_currencies() -> anyhow::Result<()> {
+        let prices = vec![Price::try_from("10 usd")?, Price::try_from("0.001 btc")?];
+        let total = sum_in(prices.iter(), &Currency::USD, &FixedRate(20000.into()))?;
+        assert_eq!(total, Price::try_from("30 usd")?);
+        Ok(())
+    }
+
     #[test]
     fn test_usd_to_btc() -> anyhow::Result<()> {
         let result = Price::try_from("50.02   usd")?;
-        let result = result.to_btc()?.currency;
+        let result = result.to_btc(&FixedRate(40000.into()))?.currency;
         let expected = Currency::BTC;
         assert!(result == expected);
         Ok(())
@@ -0,0 +1,182 @@
+use super::Currency;
+use anyhow::{format_err, Result};
+
+/// Source of exchange rates.
+pub trait RateProvider: std::fmt::Debug {
+    /// Returns the price of one BTC in the given currency. Never called for BTC denominations.
+    fn one_btc_in(&self, currency: &Currency) -> Result<rust_decimal::Decimal>;
+}
+
+/// Market rates from rate.sx.
+#[derive(Debug, Clone, Default)]
+pub struct RateSx;
+
+impl RateProvider for RateSx {
+    fn one_btc_in(&self, currency: &Currency) -> Result<rust_decimal::Decimal> {
+        let url = match currency {
+            Currency::USD => "http://rate.sx/1BTC".to_string(),
+            _ => format!(
+                "http://{currency}.rate.sx/1BTC",
+                currency = currency.to_string().to_lowercase()
+            ),
+        };
+        log::debug!("Requesting exchange rate: {}", url);
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .get(&url)
+            .header(reqwest::header::USER_AGENT, crate::HTTP_USER_AGENT)
+            .send()?
+            .text()?;
+        let response = response.replace("\n", "");
+        let one_btc = rust_decimal::Decimal::from_str_exact(&response)?;
+        Ok(one_btc)
+    }
+}
+
+/// Rates table giving the price of one BTC in each currency.
+/// Example JSON: {"USD": "30000", "EUR": "27500.5", "ETH": "15.2"}
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct StaticRates(pub std::collections::BTreeMap<Currency, rust_decimal::Decimal>);
+
+impl StaticRates {
+    /// Load rates table from a JSON file.
+    pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        let file = std::fs::File::open(&path).map_err(|error| {
+            format_err!("Failed to open rates file {}: {}", path.display(), error)
+        })?;
+        let reader = std::io::BufReader::new(file);
+        let rates: Self = serde_json::from_reader(reader)?;
+        rates
+            .check()
+            .map_err(|error| format_err!("Invalid rates file {}: {}", path.display(), error))?;
+        Ok(rates)
+    }
+
+    /// Checks that all rates are positive.
+    pub fn check(&self) -> Result<()> {
+        for (currency, rate) in &self.0 {
+            check_rate(&currency, &rate)?;
+        }
+        Ok(())
+    }
+}
+
+impl RateProvider for StaticRates {
+    fn one_btc_in(&self, currency: &Currency) -> Result<rust_decimal::Decimal> {
+        self.0.get(&currency).cloned().ok_or(format_err!(
+            "Rates table does not include currency: {}",
+            currency
+        ))
+    }
+}
+
+/// Returns an error if the given price of one BTC is not positive.
+pub fn check_rate(currency: &Currency, rate: &rust_decimal::Decimal) -> Result<()> {
+    if *rate <= rust_decimal::Decimal::from(0) {
+        return Err(format_err!(
+            "Exchange rate for {} must be positive: {}",
+            currency,
+            rate
+        ));
+    }
+    Ok(())
+}
+
+/// Single fixed price of one BTC used for every currency. Intended for tests.
+#[derive(Debug, Clone)]
+pub struct FixedRate(pub rust_decimal::Decimal);
+
+impl RateProvider for FixedRate {
+    fn one_btc_in(&self, _currency: &Currency) -> Result<rust_decimal::Decimal> {
+        Ok(self.0)
+    }
+}
+
+/// Exchange rates at a point in time. Gives the price of one BTC in each currency.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Rates {
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub rates: std::collections::BTreeMap<Currency, rust_decimal::Decimal>,
+}
+
+impl Rates {
+    pub fn new() -> Self {
+        Self {
+            time: chrono::Utc::now(),
+            rates: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+/// Consistent snapshot of exchange rates. Each rate is requested from the underlying provider at
+/// most once and then reused.
+#[derive(Debug)]
+pub struct Snapshot {
+    rates: std::cell::RefCell<Rates>,
+    provider: Box<dyn RateProvider>,
+}
+
+impl Snapshot {
+    /// Create a snapshot which extends the given rates using the given provider.
+    pub fn new(rates: Rates, provider: Box<dyn RateProvider>) -> Self {
+        Self {
+            rates: std::cell::RefCell::new(rates),
+            provider,
+        }
+    }
+
+    /// Returns the rates used so far.
+    pub fn rates(&self) -> Rates {
+        self.rates.borrow().clone()
+    }
+}
+
+impl RateProvider for Snapshot {
+    fn one_btc_in(&self, currency: &Currency) -> Result<rust_decimal::Decimal> {
+        if let Some(rate) = self.rates.borrow().rates.get(&currency) {
+            return Ok(rate.clone());
+        }
+        let rate = self.provider.one_btc_in(&currency)?;
+        self.rates
+            .borrow_mut()
+            .rates
+            .insert(currency.clone(), rate.clone());
+        Ok(rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider which counts rate requests.
+    #[derive(Debug, Default)]
+    struct CountingProvider(std::cell::Cell<usize>);
+
+    impl RateProvider for CountingProvider {
+        fn one_btc_in(&self, _currency: &Currency) -> Result<rust_decimal::Decimal> {
+            self.0.set(self.0.get() + 1);
+            Ok(rust_decimal::Decimal::from(self.0.get() * 1000))
+        }
+    }
+
+    #[test]
+    fn test_snapshot_requests_each_rate_once() -> Result<()> {
+        let snapshot = Snapshot::new(Rates::new(), Box::new(CountingProvider::default()));
+        assert_eq!(snapshot.one_btc_in(&Currency::USD)?, 1000.into());
+        assert_eq!(snapshot.one_btc_in(&Currency::USD)?, 1000.into());
+        assert_eq!(snapshot.one_btc_in(&Currency::EUR)?, 2000.into());
+        assert_eq!(snapshot.rates().rates.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_static_rates_must_be_positive() -> Result<()> {
+        let rates: StaticRates = serde_json::from_value(serde_json::json!({
+            "USD": "30000",
+            "EUR": "0"
+        }))?;
+        assert!(rates.check().is_err());
+        Ok(())
+    }
+}
@@ -22,6 +22,20 @@ pub struct Profile {
     pub unique_id: uuid::Uuid,
     #[serde(rename = "payment-methods")]
     pub payment_methods: std::collections::BTreeMap<payment_methods::Methods, serde_json::Value>,
+    #[serde(rename = "price-cap", skip_serializing_if = "Option::is_none", default)]
+    pub price_cap: Option<PriceCap>,
+}
+
+/// Maximum total price which a payee receives across all of their packages.
+/// Example: 500 USD per year
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PriceCap {
+    pub price: crate::price::Price,
+
+    /// Billing period of the cap. One-off if unset.
+    #[serde(rename = "billing-period", skip_serializing_if = "Option::is_none")]
+    pub billing_period: Option<crate::lock::plan::billing_period::BillingPeriod>,
 }
 
 impl Profile {
@@ -73,6 +87,7 @@ impl std::default::Default for Profile {
         Self {
             unique_id: uuid::Uuid::new_v4(),
             payment_methods: std::collections::BTreeMap::<_, _>::new(),
+            price_cap: None,
         }
     }
 }
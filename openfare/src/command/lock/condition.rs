@@ -13,9 +13,39 @@ pub struct ConditionArguments {
     #[structopt(long)]
     pub expiration: Option<String>,
 
-    /// Number of employees in the organization. Example: "> 100"
+    /// Date from which the plan applies. Example: "2022-01-31"
+    #[structopt(name = "effective-from", long = "effective-from")]
+    pub effective_from: Option<String>,
+
+    /// Number of employees in the organization. Example: ">= 250"
     #[structopt(name = "employees-count", long = "employees-count")]
     pub employees_count: Option<String>,
+
+    /// Annual revenue of the organization. Example: "1M USD <= revenue < 10M USD"
+    #[structopt(name = "annual-revenue", long = "annual-revenue")]
+    pub annual_revenue: Option<String>,
+
+    /// Countries within which the plan applies (ISO 3166-1 alpha-2). Example: "GB,US"
+    #[structopt(long = "country-include", use_delimiter = true)]
+    pub country_include: Vec<String>,
+
+    /// Countries within which the plan does not apply (ISO 3166-1 alpha-2). Example: "CU,KP"
+    #[structopt(long = "country-exclude", use_delimiter = true)]
+    pub country_exclude: Vec<String>,
+
+    /// Ways of using the software to which the plan applies. Example: "internal,redistributed"
+    /// Values: internal, hosted-service, redistributed, embedded-device
+    #[structopt(long, use_delimiter = true)]
+    pub usage: Vec<String>,
+
+    /// Kinds of dependency to which the plan applies. Example: "runtime"
+    /// Values: runtime, dev, build, optional
+    #[structopt(long = "dependency-kind", use_delimiter = true)]
+    pub dependency_kind: Vec<String>,
+
+    /// Package versions to which the plan applies (semver requirement). Example: ">=3.0.0"
+    #[structopt(long)]
+    pub version: Option<String>,
 }
 
 impl std::convert::TryInto<openfare_lib::lock::plan::conditions::Conditions>
@@ -35,6 +65,15 @@ impl std::convert::TryInto<openfare_lib::lock::plan::conditions::Conditions>
         } else {
             None
         };
+        let effective_from = if let Some(effective_from) = &self.effective_from {
+            Some(
+                openfare_lib::lock::plan::conditions::EffectiveFrom::try_from(
+                    effective_from.as_str(),
+                )?,
+            )
+        } else {
+            None
+        };
         let employees_count = if let Some(employees_count) = &self.employees_count {
             Some(
                 openfare_lib::lock::plan::conditions::EmployeesCount::try_from(
@@ -45,10 +84,62 @@ impl std::convert::TryInto<openfare_lib::lock::plan::conditions::Conditions>
             None
         };
 
+        let annual_revenue = if let Some(annual_revenue) = &self.annual_revenue {
+            Some(
+                openfare_lib::lock::plan::conditions::AnnualRevenue::try_from(
+                    annual_revenue.as_str(),
+                )?,
+            )
+        } else {
+            None
+        };
+
+        let country = if !self.country_include.is_empty() || !self.country_exclude.is_empty() {
+            Some(openfare_lib::lock::plan::conditions::Country::new(
+                &self.country_include,
+                &self.country_exclude,
+            )?)
+        } else {
+            None
+        };
+
+        let usage = if !self.usage.is_empty() {
+            Some(openfare_lib::lock::plan::conditions::Usage::try_from(
+                &self.usage,
+            )?)
+        } else {
+            None
+        };
+
+        let dependency_kind = if !self.dependency_kind.is_empty() {
+            Some(
+                openfare_lib::lock::plan::conditions::DependencyKind::try_from(
+                    &self.dependency_kind,
+                )?,
+            )
+        } else {
+            None
+        };
+
+        let version = if let Some(version) = &self.version {
+            Some(openfare_lib::lock::plan::conditions::Version::try_from(
+                version.as_str(),
+            )?)
+        } else {
+            None
+        };
+
         Ok(openfare_lib::lock::plan::conditions::Conditions {
             for_profit,
             expiration,
+            effective_from,
             employees_count,
+            annual_revenue,
+            country,
+            usage,
+            dependency_kind,
+            version,
+            ..Default::default()
         })
     }
 }
@@ -67,12 +158,36 @@ pub struct AddArguments {
     #[structopt(flatten)]
     pub conditions: ConditionArguments,
 
+    /// Add given conditions as a single expression which passes if any condition passes.
+    #[structopt(long)]
+    pub any: bool,
+
+    /// Add given conditions as a single expression which passes if the conditions do not pass.
+    /// Combine with --any to require that none of the conditions pass.
+    #[structopt(long)]
+    pub not: bool,
+
     #[structopt(flatten)]
     pub lock_file_args: common::LockFilePathArg,
 }
 
 pub fn add(args: &AddArguments) -> Result<()> {
-    let conditions = args.conditions.clone().try_into()?;
+    let conditions: openfare_lib::lock::plan::conditions::Conditions =
+        args.conditions.clone().try_into()?;
+    let compound_conditions = if args.any || args.not {
+        if conditions.as_vec().is_empty() {
+            return Err(anyhow::format_err!(
+                "No conditions given for compound condition expression."
+            ));
+        }
+        Some(get_compound_conditions(
+            conditions.clone(),
+            args.any,
+            args.not,
+        ))
+    } else {
+        None
+    };
 
     let plan_ids = args
         .id
@@ -86,12 +201,45 @@ pub fn add(args: &AddArguments) -> Result<()> {
         .iter_mut()
         .filter(|(id, _plan)| plan_ids.contains(id.as_str()) || plan_ids.is_empty())
     {
-        plan.conditions.set_some(&conditions);
+        if let Some(compound_conditions) = &compound_conditions {
+            plan.conditions.push(compound_conditions.clone());
+        } else {
+            plan.conditions.set_some(&conditions);
+        }
     }
 
     Ok(())
 }
 
+/// Combines conditions into a compound condition expression.
+fn get_compound_conditions(
+    conditions: openfare_lib::lock::plan::conditions::Conditions,
+    any: bool,
+    not: bool,
+) -> openfare_lib::lock::plan::conditions::Conditions {
+    let conditions = if any {
+        // Each condition becomes a separate alternative.
+        openfare_lib::lock::plan::conditions::Conditions {
+            any: Some(openfare_lib::lock::plan::conditions::Any(
+                conditions.split(),
+            )),
+            ..Default::default()
+        }
+    } else {
+        conditions
+    };
+    if not {
+        openfare_lib::lock::plan::conditions::Conditions {
+            not: Some(openfare_lib::lock::plan::conditions::Not(Box::new(
+                conditions,
+            ))),
+            ..Default::default()
+        }
+    } else {
+        conditions
+    }
+}
+
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
     name = "no_version",
@@ -111,10 +259,38 @@ pub struct RemoveArguments {
     #[structopt(long = "employees-count")]
     pub employees_count: bool,
 
+    /// Annual revenue of the organization.
+    #[structopt(long = "annual-revenue")]
+    pub annual_revenue: bool,
+
+    /// Country inclusions and exclusions.
+    #[structopt(long)]
+    pub country: bool,
+
+    /// Usage.
+    #[structopt(long)]
+    pub usage: bool,
+
+    /// Dependency kind.
+    #[structopt(long = "dependency-kind")]
+    pub dependency_kind: bool,
+
+    /// Package version requirement.
+    #[structopt(long)]
+    pub version: bool,
+
     /// Expiration date.
     #[structopt(long)]
     pub expiration: bool,
 
+    /// Date from which the plan applies.
+    #[structopt(long = "effective-from")]
+    pub effective_from: bool,
+
+    /// Compound condition expressions (all, any, not).
+    #[structopt(long)]
+    pub compound: bool,
+
     /// Remove all conditions.
     #[structopt(long, short)]
     pub all: bool,
@@ -142,9 +318,32 @@ pub fn remove(args: &RemoveArguments) -> Result<()> {
         if args.expiration || args.all {
             plan.conditions.expiration = None;
         }
+        if args.effective_from || args.all {
+            plan.conditions.effective_from = None;
+        }
         if args.employees_count || args.all {
             plan.conditions.employees_count = None;
         }
+        if args.annual_revenue || args.all {
+            plan.conditions.annual_revenue = None;
+        }
+        if args.country || args.all {
+            plan.conditions.country = None;
+        }
+        if args.usage || args.all {
+            plan.conditions.usage = None;
+        }
+        if args.dependency_kind || args.all {
+            plan.conditions.dependency_kind = None;
+        }
+        if args.version || args.all {
+            plan.conditions.version = None;
+        }
+        if args.compound || args.all {
+            plan.conditions.all = None;
+            plan.conditions.any = None;
+            plan.conditions.not = None;
+        }
     }
     Ok(())
 }
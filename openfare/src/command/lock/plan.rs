@@ -35,6 +35,20 @@ pub struct AddCompulsoryArguments {
     #[structopt(long, short)]
     pub price: String,
 
+    /// Price per unit. Example: "seat"
+    /// Values: seat, instance, 1000-end-users
+    #[structopt(long)]
+    pub unit: Option<openfare_lib::lock::plan::unit::Unit>,
+
+    /// How often the price is paid. One-off if unset.
+    /// Values: one-off, monthly, yearly
+    #[structopt(long = "billing-period")]
+    pub billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+
+    /// Priority used by the priority plan selection policy. Lower numbers are preferred.
+    #[structopt(long)]
+    pub priority: Option<u32>,
+
     #[structopt(flatten)]
     pub conditions: super::condition::ConditionArguments,
 
@@ -52,6 +66,9 @@ pub fn add_compulsory(args: &AddCompulsoryArguments) -> Result<()> {
         r#type: openfare_lib::lock::plan::PlanType::Compulsory,
         conditions,
         price: Some(args.price.parse().expect("parse price")),
+        unit: args.unit.clone(),
+        billing_period: args.billing_period.clone(),
+        priority: args.priority,
     };
     lock_handle.lock.plans.insert(id.clone(), plan.clone());
 
@@ -69,6 +86,10 @@ pub fn add_compulsory(args: &AddCompulsoryArguments) -> Result<()> {
     global_settings = &[structopt::clap::AppSettings::DisableVersion]
 )]
 pub struct AddVoluntaryArguments {
+    /// Priority used by the priority plan selection policy. Lower numbers are preferred.
+    #[structopt(long)]
+    pub priority: Option<u32>,
+
     #[structopt(flatten)]
     pub lock_file_args: common::LockFilePathArg,
 }
@@ -80,6 +101,9 @@ pub fn add_voluntary(args: &AddVoluntaryArguments) -> Result<()> {
         r#type: openfare_lib::lock::plan::PlanType::Voluntary,
         conditions: openfare_lib::lock::plan::conditions::Conditions::default(),
         price: None,
+        unit: None,
+        billing_period: None,
+        priority: args.priority,
     };
     lock_handle.lock.plans.insert(id.clone(), plan.clone());
 
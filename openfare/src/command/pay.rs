@@ -26,6 +26,11 @@ pub struct Arguments {
     /// Example values: py, js, rs
     #[structopt(long = "extension", short = "e", name = "name")]
     pub extension_names: Option<Vec<String>>,
+
+    /// Only pay for dependencies of the given kinds. Example: "runtime,optional"
+    /// Values: runtime, dev, build, optional
+    #[structopt(long = "dependency-kind", use_delimiter = true)]
+    pub dependency_kinds: Vec<openfare_lib::package::DependencyKind>,
 }
 
 pub fn run_command(args: &Arguments, extension_args: &Vec<String>) -> Result<()> {
@@ -40,7 +45,19 @@ pub fn run_command(args: &Arguments, extension_args: &Vec<String>) -> Result<()>
 
     let extensions = extensions::manage::from_names_arg(&args.extension_names, &config)?;
     let all_extension_locks = get_locks(&extensions, &extension_args)?;
+    let all_extension_locks = all_extension_locks
+        .into_iter()
+        .map(|extension_locks| ExtensionLocks {
+            package_locks: extension_locks
+                .package_locks
+                .filter_dependencies_kinds(&args.dependency_kinds),
+            ..extension_locks
+        })
+        .collect::<Vec<_>>();
+    let rates_snapshot = crate::rates::snapshot(&config)?;
     let mut items = vec![];
+    let mut plan_prices = vec![];
+    let mut fees = crate::payments::CompulsoryFees::new();
     for extension_locks in all_extension_locks {
         if !openfare_lib::lock::plan::conditions::parameters::check_set(
             &extension_locks.package_locks.conditions_metadata(),
@@ -49,10 +66,130 @@ pub fn run_command(args: &Arguments, extension_args: &Vec<String>) -> Result<()>
             config.dump()?;
         }
 
-        let basket_items = get_basket_items(&extension_locks, &config)?;
+        let basket_items = get_basket_items(&extension_locks, &rates_snapshot, &config)?;
+        for item in &basket_items {
+            let parameters = config.profile.parameters.for_package(
+                &item.package,
+                &extension_locks.package_locks.dependency_kind(&item.package),
+            );
+            plan_prices.push(get_plan_prices(&item, &parameters)?);
+            let fee = crate::payments::compulsory_fee(&item, &parameters, &rates_snapshot)?;
+            fees.insert(item.package.clone(), fee);
+        }
         items.extend(basket_items);
     }
-    crate::services::pay(&args.donation, &items, &args.service, &config)?;
+    apply_price_caps(&mut items, &plan_prices, &mut fees, &rates_snapshot)?;
+    let paid_items = crate::services::pay(
+        &args.donation,
+        &items,
+        &fees,
+        &args.service,
+        &rates_snapshot,
+        &config,
+    )?;
+    crate::rates::store(&rates_snapshot, &config)?;
+    if let Some(paid_items) = paid_items {
+        record_payments(&paid_items, &args.donation, &rates_snapshot.rates())?;
+    }
+    Ok(())
+}
+
+/// Returns the prices of a basket item's plans along with their billing periods.
+fn get_plan_prices(
+    item: &openfare_lib::api::services::basket::Item,
+    parameters: &openfare_lib::lock::plan::conditions::Parameters,
+) -> Result<
+    Vec<(
+        openfare_lib::price::Price,
+        openfare_lib::lock::plan::billing_period::BillingPeriod,
+    )>,
+> {
+    let mut plan_prices = vec![];
+    for plan in item.plans.values() {
+        if let Some(price) = plan.total_price(&parameters)? {
+            plan_prices.push((price, plan.billing_period.clone().unwrap_or_default()));
+        }
+    }
+    Ok(plan_prices)
+}
+
+/// Reduces basket item prices and compulsory fees according to payee price caps. Plan prices
+/// are given for each item.
+fn apply_price_caps(
+    items: &mut Vec<openfare_lib::api::services::basket::Item>,
+    plan_prices: &Vec<
+        Vec<(
+            openfare_lib::price::Price,
+            openfare_lib::lock::plan::billing_period::BillingPeriod,
+        )>,
+    >,
+    fees: &mut crate::payments::CompulsoryFees,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+) -> Result<()> {
+    let package_prices = items
+        .iter()
+        .zip(plan_prices)
+        .map(|(item, plan_prices)| {
+            (
+                item.payees.clone(),
+                item.shares.clone(),
+                item.total_price.clone(),
+                plan_prices.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let capped_prices =
+        openfare_lib::lock::payee::apply_price_caps(&package_prices, rate_provider)?;
+    for (item, capped_price) in items.iter_mut().zip(capped_prices) {
+        if capped_price.quantity < item.total_price.quantity {
+            println!(
+                "Payee price cap applied to {name} ({version}): {price} reduced to {capped_price}",
+                name = item.package.name,
+                version = item.package.version,
+                price = item.total_price,
+                capped_price = capped_price
+            );
+            if let Some(fee) = fees.get_mut(&item.package) {
+                // Round down to avoid exceeding the cap.
+                fee.quantity = (fee.quantity * capped_price.quantity / item.total_price.quantity)
+                    .round_dp_with_strategy(
+                        fee.currency.decimal_points(),
+                        rust_decimal::prelude::RoundingStrategy::ToZero,
+                    );
+            }
+            item.total_price = capped_price;
+        }
+    }
+    Ok(())
+}
+
+/// Records paid plans and donation along with the exchange rates used and reports when
+/// recurring payments next fall due.
+fn record_payments(
+    items: &Vec<openfare_lib::api::services::basket::Item>,
+    donation: &Option<openfare_lib::price::Price>,
+    rates: &openfare_lib::price::Rates,
+) -> Result<()> {
+    let paid = chrono::Utc::now();
+    let mut schedule = crate::schedule::Schedule::load()?;
+    let payments = schedule.record(&items, &paid, &rates);
+    if let Some(donation) = donation {
+        schedule.record_donation(&donation, &paid, &rates);
+    }
+    schedule.dump()?;
+
+    for payment in payments {
+        if let Some(next_due) = payment.next_due {
+            println!(
+                "Next {billing_period} payment for {name} ({version}) plan {plan_id} due: {next_due}",
+                billing_period = payment.billing_period.to_string(),
+                name = payment.package.name,
+                version = payment.package.version,
+                plan_id = payment.plan_id,
+                next_due = next_due.format("%Y-%m-%d")
+            );
+        }
+    }
     Ok(())
 }
 
@@ -94,6 +231,7 @@ pub fn get_locks(
 /// Get applicable payment plans from dependencies packages.
 pub fn get_basket_items(
     extension_locks: &ExtensionLocks,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
     config: &crate::config::Config,
 ) -> Result<Vec<openfare_lib::api::services::basket::Item>> {
     let mut basket_items: Vec<_> = vec![];
@@ -102,17 +240,40 @@ pub fn get_basket_items(
             Some(lock) => lock,
             None => continue,
         };
-        let plans =
-            openfare_lib::lock::plan::filter_applicable(&lock.plans, &config.profile.parameters)?;
+        let parameters = config.profile.parameters.for_package(
+            &package,
+            &extension_locks.package_locks.dependency_kind(&package),
+        );
+        let filtered_plans = openfare_lib::lock::plan::filter_applicable(&lock.plans, &parameters)?;
+        for (plan_id, reason) in &filtered_plans.undecidable {
+            println!(
+                "Skipping plan {plan_id} for package {name} ({version}): {reason}",
+                plan_id = plan_id,
+                name = package.name,
+                version = package.version,
+                reason = reason
+            );
+        }
+        let plans = lock.select_plans(
+            &filtered_plans.applicable,
+            &config.core.plan_selection,
+            &parameters,
+            rate_provider,
+        )?;
         if plans.is_empty() {
             // Skip package if no applicable plans found.
             continue;
         }
 
-        let total_price = plans
+        let plans_prices = plans
             .iter()
-            .map(|(_id, plan)| plan.price.clone().unwrap_or_default())
-            .sum();
+            .filter_map(|(_id, plan)| plan.total_price(&parameters).transpose())
+            .collect::<Result<Vec<_>>>()?;
+        let total_price = openfare_lib::price::sum_in(
+            plans_prices.iter(),
+            &config.core.preferred_currency,
+            rate_provider,
+        )?;
 
         let item = openfare_lib::api::services::basket::Item {
             package: package.clone(),
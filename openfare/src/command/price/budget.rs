@@ -0,0 +1,144 @@
+use super::common;
+use anyhow::Result;
+
+/// Checks compulsory fees against the given budget. Limits are converted into the report
+/// currency.
+///
+/// Returns a line describing each exceeded limit. Lines are sorted so that output from
+/// successive runs can be compared.
+pub fn check(
+    price_reports: &Vec<common::PriceReport>,
+    budget: &crate::config::Budget,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+) -> Result<Vec<String>> {
+    let mut exceeded = vec![];
+    let mut total: Option<openfare_lib::price::Price> = None;
+
+    for price_report in price_reports {
+        let currency = &price_report.price.currency;
+        for package_report in &price_report.package_reports {
+            // Voluntary plan prices do not count against the budget.
+            let quantity = match package_report.compulsory_price_quantity {
+                Some(quantity) => quantity,
+                None => continue,
+            };
+            let price = openfare_lib::price::Price {
+                quantity,
+                currency: currency.clone(),
+            };
+            total = Some(match total {
+                Some(total) => {
+                    let price = price.to(&total.currency, rate_provider)?;
+                    (total + price)?
+                }
+                None => price.clone(),
+            });
+
+            let package = &package_report.package;
+            if let Some(limit) = budget.packages.get(&package.name) {
+                let limit = limit.to(&currency, rate_provider)?;
+                if price.quantity > limit.quantity {
+                    exceeded.push(format!(
+                        "package {registry} {name} {version}: {price} exceeds budget {limit}",
+                        registry = package.registry,
+                        name = package.name,
+                        version = package.version,
+                        price = price,
+                        limit = limit
+                    ));
+                }
+            }
+        }
+    }
+
+    if let (Some(limit), Some(total)) = (&budget.total, &total) {
+        let limit = limit.to(&total.currency, rate_provider)?;
+        if total.quantity > limit.quantity {
+            exceeded.push(format!(
+                "total: {total} exceeds budget {limit}",
+                total = total,
+                limit = limit
+            ));
+        }
+    }
+    exceeded.sort();
+    Ok(exceeded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::price::tests::{package_report, price_report};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_check_ignores_voluntary_portion() -> Result<()> {
+        // Cumulative selection of a compulsory plan and a priced voluntary plan.
+        let mut package_report = package_report(
+            "a",
+            "1.0.0",
+            openfare_lib::lock::plan::PlanType::Compulsory,
+            30,
+        );
+        package_report.plan_ids = vec!["0".to_string(), "1".to_string()];
+        package_report.compulsory_price_quantity = Some(20.into());
+        let price_report = price_report(vec![package_report], "30 USD")?;
+
+        let mut budget = crate::config::Budget::default();
+        budget.total = Some(openfare_lib::price::Price::from_str("25 USD")?);
+        budget.packages.insert(
+            "a".to_string(),
+            openfare_lib::price::Price::from_str("25 USD")?,
+        );
+
+        let exceeded = check(
+            &vec![price_report],
+            &budget,
+            &openfare_lib::price::FixedRate(30000.into()),
+        )?;
+        assert!(exceeded.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_exceeded_limits() -> Result<()> {
+        let compulsory = openfare_lib::lock::plan::PlanType::Compulsory;
+        let price_report = price_report(
+            vec![
+                package_report("a", "1.0.0", compulsory.clone(), 30),
+                package_report("b", "1.0.0", compulsory.clone(), 50),
+                package_report(
+                    "c",
+                    "1.0.0",
+                    openfare_lib::lock::plan::PlanType::Voluntary,
+                    500,
+                ),
+            ],
+            "580 USD",
+        )?;
+        let mut budget = crate::config::Budget::default();
+        budget.total = Some(openfare_lib::price::Price::from_str("0.002 BTC")?);
+        budget.packages.insert(
+            "a".to_string(),
+            openfare_lib::price::Price::from_str("20 USD")?,
+        );
+        budget.packages.insert(
+            "b".to_string(),
+            openfare_lib::price::Price::from_str("60 USD")?,
+        );
+
+        let exceeded = check(
+            &vec![price_report],
+            &budget,
+            &openfare_lib::price::FixedRate(30000.into()),
+        )?;
+        assert_eq!(
+            exceeded,
+            vec![
+                "package npmjs.com a 1.0.0: 30.00 USD exceeds budget 20.00 USD".to_string(),
+                "total: 80.00 USD exceeds budget 60.00 USD".to_string(),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,135 +1,569 @@
 use anyhow::Result;
 
+/// Generates a price report. Prices are converted into the preferred currency. Recurring prices
+/// are normalized to the given billing period if one is given, in which case one-off prices are
+/// totalled separately.
 pub fn get_report(
     package_locks: &openfare_lib::package::PackageLocks,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
     config: &crate::config::Config,
 ) -> Result<Option<PriceReport>> {
     log::info!("Generating price report for package and it's dependencies.");
 
     // Handle primary package first.
-    let mut package_reports = vec![];
+    let mut package_prices = vec![];
+    let mut locks = vec![];
 
     if let Some(primary_package) = &package_locks.primary_package {
-        let primary_package_price_report = get_package_price_report(
+        let primary_package_prices = get_package_price_report(
             &primary_package,
             &package_locks.primary_package_lock,
+            &openfare_lib::package::DependencyKind::Runtime,
+            &billing_period,
+            rate_provider,
             &config,
         )?;
-        package_reports.push(primary_package_price_report);
+        package_prices.push(primary_package_prices);
+        locks.push(&package_locks.primary_package_lock);
     }
 
     for (package, package_lock) in &package_locks.dependencies_locks {
-        let price_report = get_package_price_report(&package, &package_lock, &config)?;
-        package_reports.push(price_report);
+        let prices = get_package_price_report(
+            &package,
+            &package_lock,
+            &package_locks.dependency_kind(&package),
+            &billing_period,
+            rate_provider,
+            &config,
+        )?;
+        package_prices.push(prices);
+        locks.push(package_lock);
     }
 
     log::info!(
         "Number of package price reports generated: {}",
-        package_reports.len()
+        package_prices.len()
     );
-    if package_reports.is_empty() {
+    if package_prices.is_empty() {
         return Ok(None);
     }
+    apply_price_caps(&mut package_prices, &locks, rate_provider, &config)?;
+
+    let one_off_quantity: openfare_lib::price::Quantity = package_prices
+        .iter()
+        .map(|prices| prices.one_off_quantity)
+        .sum();
+    let package_reports = package_prices
+        .into_iter()
+        .map(|prices| prices.report)
+        .collect::<Vec<_>>();
 
-    let total_price = package_reports
+    let total_price: Result<openfare_lib::price::Price> = package_reports
         .iter()
-        .map(|r| r.price_quantity.unwrap_or(rust_decimal::Decimal::from(0)))
-        .sum::<rust_decimal::Decimal>();
+        .map(|r| openfare_lib::price::Price {
+            quantity: r.price_quantity.unwrap_or(rust_decimal::Decimal::from(0)),
+            currency: config.core.preferred_currency.clone(),
+        })
+        .sum();
+
+    let payee_reports =
+        get_payee_reports(&package_reports, &locks, &config.core.preferred_currency);
+
+    let one_off_price = if billing_period.is_some() && !one_off_quantity.is_zero() {
+        Some(openfare_lib::price::Price {
+            quantity: one_off_quantity,
+            currency: config.core.preferred_currency.clone(),
+        })
+    } else {
+        None
+    };
 
     let price_report = PriceReport {
         package_reports: package_reports,
-        price: openfare_lib::price::Price {
-            quantity: rust_decimal::Decimal::from(total_price),
-            currency: config.core.preferred_currency.clone(),
-        },
+        payee_reports: payee_reports,
+        price: total_price?,
+        billing_period: billing_period.clone(),
+        one_off_price,
     };
     Ok(Some(price_report))
 }
 
+/// Reduces package prices according to payee price caps.
+fn apply_price_caps(
+    package_prices: &mut Vec<PackagePrices>,
+    locks: &Vec<&Option<openfare_lib::lock::Lock>>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+    config: &crate::config::Config,
+) -> Result<()> {
+    let caps_package_prices = package_prices
+        .iter()
+        .zip(locks)
+        .map(|(prices, lock)| {
+            let (payees, shares) = match lock {
+                Some(lock) => (lock.payees.clone(), lock.shares.clone()),
+                None => (Default::default(), None),
+            };
+            let price = openfare_lib::price::Price {
+                quantity: prices.report.price_quantity.unwrap_or_default(),
+                currency: config.core.preferred_currency.clone(),
+            };
+            (payees, shares, price, prices.plan_prices.clone())
+        })
+        .collect::<Vec<_>>();
+    let capped_prices =
+        openfare_lib::lock::payee::apply_price_caps(&caps_package_prices, rate_provider)?;
+
+    for ((prices, (_payees, _shares, price, _plan_prices)), capped_price) in package_prices
+        .iter_mut()
+        .zip(&caps_package_prices)
+        .zip(capped_prices)
+    {
+        if capped_price.quantity < price.quantity {
+            prices.report.notes.push(format!(
+                "Payee price cap applied: {price} reduced to {capped_price}",
+                price = price.to_symbolic(),
+                capped_price = capped_price.to_symbolic()
+            ));
+            // Reduce the compulsory portion in proportion to the package price.
+            prices.report.compulsory_price_quantity =
+                prices.report.compulsory_price_quantity.map(|quantity| {
+                    (quantity * capped_price.quantity / price.quantity)
+                        .round_dp(price.currency.decimal_points())
+                });
+            prices.report.price_quantity = Some(capped_price.quantity);
+        }
+    }
+    Ok(())
+}
+
+/// Aggregates package prices by payee using the lock shares.
+fn get_payee_reports(
+    package_reports: &Vec<PackagePriceReport>,
+    locks: &Vec<&Option<openfare_lib::lock::Lock>>,
+    currency: &openfare_lib::price::Currency,
+) -> Vec<PayeePriceReport> {
+    let mut payee_reports = std::collections::BTreeMap::<uuid::Uuid, PayeePriceReport>::new();
+    for (report, lock) in package_reports.iter().zip(locks) {
+        let (lock, price_quantity) = match (lock, report.price_quantity) {
+            (Some(lock), Some(price_quantity)) if !price_quantity.is_zero() => {
+                (lock, price_quantity)
+            }
+            _ => continue,
+        };
+        for (payee, fraction) in openfare_lib::lock::payee::fractions(&lock.payees, &lock.shares) {
+            let label = openfare_lib::lock::payee::get_lock_payee(&payee.profile, &lock.payees)
+                .map(|(label, _payee)| label)
+                .unwrap_or_default();
+            let payee_report =
+                payee_reports
+                    .entry(payee.profile.unique_id)
+                    .or_insert(PayeePriceReport {
+                        label,
+                        unique_id: payee.profile.unique_id,
+                        price_quantity: rust_decimal::Decimal::from(0),
+                        packages: vec![],
+                    });
+            let package_quantity = (price_quantity * fraction).round_dp(currency.decimal_points());
+            payee_report.price_quantity += package_quantity;
+            payee_report
+                .packages
+                .push((report.package.clone(), package_quantity));
+        }
+    }
+    payee_reports.into_values().collect()
+}
+
+/// Combines price reports, such as those given by each extension, into a single report.
+/// Payee reports are merged by payee unique ID. Prices are summed in the given currency.
+pub fn combine(
+    price_reports: &Vec<PriceReport>,
+    currency: &openfare_lib::price::Currency,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+) -> Result<PriceReport> {
+    let price = openfare_lib::price::sum_in(
+        price_reports.iter().map(|price_report| &price_report.price),
+        &currency,
+        rate_provider,
+    )?;
+    let one_off_prices = price_reports
+        .iter()
+        .filter_map(|price_report| price_report.one_off_price.as_ref())
+        .collect::<Vec<_>>();
+    let one_off_price = if one_off_prices.is_empty() {
+        None
+    } else {
+        Some(openfare_lib::price::sum_in(
+            one_off_prices.into_iter(),
+            &currency,
+            rate_provider,
+        )?)
+    };
+
+    let mut payee_reports = std::collections::BTreeMap::<uuid::Uuid, PayeePriceReport>::new();
+    for payee_report in price_reports
+        .iter()
+        .flat_map(|price_report| &price_report.payee_reports)
+    {
+        match payee_reports.get_mut(&payee_report.unique_id) {
+            Some(combined) => {
+                combined.price_quantity += payee_report.price_quantity;
+                combined.packages.extend(payee_report.packages.clone());
+            }
+            None => {
+                payee_reports.insert(payee_report.unique_id, payee_report.clone());
+            }
+        }
+    }
+
+    Ok(PriceReport {
+        package_reports: price_reports
+            .iter()
+            .flat_map(|price_report| price_report.package_reports.clone())
+            .collect(),
+        payee_reports: payee_reports.into_values().collect(),
+        price,
+        billing_period: billing_period.clone(),
+        one_off_price,
+    })
+}
+
+/// Prints the total price of a report.
+pub fn print_total(price_report: &PriceReport) {
+    match &price_report.billing_period {
+        Some(billing_period) => println!(
+            "Total: {} ({})",
+            price_report.price,
+            billing_period.to_string()
+        ),
+        None => println!("Total: {}", price_report.price),
+    }
+    if let Some(one_off_price) = &price_report.one_off_price {
+        println!("One-off (not included in total): {}", one_off_price);
+    }
+}
+
 #[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
 pub struct PriceReport {
     pub package_reports: Vec<PackagePriceReport>,
+    pub payee_reports: Vec<PayeePriceReport>,
     pub price: openfare_lib::price::Price,
+
+    /// Billing period to which recurring prices are normalized.
+    pub billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+
+    /// Total of one-off prices. Set if prices are normalized to a billing period, in which case
+    /// one-off prices are not included in the total price.
+    pub one_off_price: Option<openfare_lib::price::Price>,
 }
 
 #[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
 pub struct PackagePriceReport {
     pub package: openfare_lib::package::Package,
-    pub plan_id: Option<openfare_lib::lock::plan::Id>,
+    /// Plans selected by the plan selection policy.
+    pub plan_ids: Vec<openfare_lib::lock::plan::Id>,
+    /// Type of the selected plans. Compulsory if any selected plan is compulsory.
+    pub plan_type: Option<openfare_lib::lock::plan::PlanType>,
     pub price_quantity: Option<openfare_lib::price::Quantity>,
+
+    /// Portion of the price given by compulsory plans. None if no compulsory plan is selected.
+    pub compulsory_price_quantity: Option<openfare_lib::price::Quantity>,
     pub notes: Vec<String>,
+
+    /// Labels of the payees given in the package's lock.
+    pub payees: Vec<openfare_lib::lock::payee::Label>,
+}
+
+#[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
+pub struct PayeePriceReport {
+    /// Payee label used in the first lock which includes the payee.
+    pub label: openfare_lib::lock::payee::Label,
+    pub unique_id: uuid::Uuid,
+    pub price_quantity: openfare_lib::price::Quantity,
+
+    /// Packages which contribute to the payee's total and their contributions.
+    pub packages: Vec<(
+        openfare_lib::package::Package,
+        openfare_lib::price::Quantity,
+    )>,
+}
+
+/// A package price report along with the prices from which the report price is derived.
+struct PackagePrices {
+    report: PackagePriceReport,
+
+    /// Total of one-off prices which are not included in the report price.
+    one_off_quantity: openfare_lib::price::Quantity,
+
+    /// Prices of the plans included in the report price, before normalization and currency
+    /// conversion, and their billing periods.
+    plan_prices: Vec<(
+        openfare_lib::price::Price,
+        openfare_lib::lock::plan::billing_period::BillingPeriod,
+    )>,
 }
 
 /// Given a package's OpenFare lock, create a corresponding price report.
 fn get_package_price_report(
     package: &openfare_lib::package::Package,
     package_lock: &Option<openfare_lib::lock::Lock>,
+    dependency_kind: &openfare_lib::package::DependencyKind,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
     config: &crate::config::Config,
-) -> Result<PackagePriceReport> {
+) -> Result<PackagePrices> {
     let package_lock = match package_lock {
         Some(lock) => lock,
         None => {
-            return Ok(PackagePriceReport {
-                package: package.clone(),
-                plan_id: None,
-                price_quantity: None,
-                notes: vec![],
+            return Ok(PackagePrices {
+                report: PackagePriceReport {
+                    package: package.clone(),
+                    plan_ids: vec![],
+                    plan_type: None,
+                    price_quantity: None,
+                    compulsory_price_quantity: None,
+                    notes: vec![],
+                    payees: vec![],
+                },
+                one_off_quantity: rust_decimal::Decimal::from(0),
+                plan_prices: vec![],
             });
         }
     };
 
-    let applicable_plans = openfare_lib::lock::plan::filter_applicable(
-        &package_lock.plans,
-        &config.profile.parameters,
+    let parameters = config
+        .profile
+        .parameters
+        .for_package(&package, &dependency_kind);
+    let filtered_plans =
+        openfare_lib::lock::plan::filter_applicable(&package_lock.plans, &parameters)?;
+    let mut notes = get_undecidable_notes(&filtered_plans);
+    let payees = package_lock.payees.keys().cloned().collect::<Vec<_>>();
+
+    let selected_plans = package_lock.select_plans(
+        &filtered_plans.applicable,
+        &config.core.plan_selection,
+        &parameters,
+        rate_provider,
     )?;
 
-    Ok(
-        if let Some((plan_id, plan)) = select_plan(&applicable_plans) {
-            PackagePriceReport {
-                package: package.clone(),
-                plan_id: Some((*plan_id).clone()),
-                price_quantity: Some(if let Some(price) = &plan.price {
-                    price.quantity
-                } else {
-                    rust_decimal::Decimal::from(0)
-                }),
-                notes: vec![],
+    let mut price_quantity = rust_decimal::Decimal::from(0);
+    let mut compulsory_price_quantity = rust_decimal::Decimal::from(0);
+    let mut one_off_quantity = rust_decimal::Decimal::from(0);
+    let mut plan_prices = vec![];
+    for (plan_id, plan) in &selected_plans {
+        let mut price = match plan.total_price(&parameters)? {
+            Some(price) => price,
+            None => continue,
+        };
+        if let Some(note) = get_unit_price_note(&plan_id, &plan, &price, &parameters) {
+            notes.push(note);
+        }
+        let plan_billing_period = plan.billing_period.clone().unwrap_or_default();
+        let is_one_off = billing_period.is_some()
+            && plan_billing_period
+                == openfare_lib::lock::plan::billing_period::BillingPeriod::OneOff;
+        if !is_one_off {
+            plan_prices.push((price.clone(), plan_billing_period));
+        }
+        if let Some(billing_period) = billing_period {
+            if let Some(note) = normalize_price(&plan_id, &plan, &mut price, &billing_period) {
+                notes.push(note);
             }
+        }
+        if price.currency != config.core.preferred_currency {
+            let converted_price = price.to(&config.core.preferred_currency, rate_provider)?;
+            notes.push(format!(
+                "Plan {plan_id}: {price} = {converted_price}",
+                plan_id = plan_id,
+                price = price.to_symbolic(),
+                converted_price = converted_price.to_symbolic()
+            ));
+            price = converted_price;
+        }
+        if is_one_off {
+            one_off_quantity += price.quantity;
         } else {
-            PackagePriceReport {
-                package: package.clone(),
-                plan_id: None,
-                price_quantity: Some(rust_decimal::Decimal::from(0)),
-                notes: vec![],
+            price_quantity += price.quantity;
+            if plan.r#type == openfare_lib::lock::plan::PlanType::Compulsory {
+                compulsory_price_quantity += price.quantity;
             }
+        }
+    }
+    let plan_type = get_plan_type(&selected_plans);
+    let compulsory_price_quantity = match plan_type {
+        Some(openfare_lib::lock::plan::PlanType::Compulsory) => Some(compulsory_price_quantity),
+        _ => None,
+    };
+
+    Ok(PackagePrices {
+        report: PackagePriceReport {
+            package: package.clone(),
+            plan_ids: selected_plans.keys().cloned().collect(),
+            plan_type,
+            price_quantity: Some(price_quantity),
+            compulsory_price_quantity,
+            notes,
+            payees,
         },
-    )
+        one_off_quantity,
+        plan_prices,
+    })
 }
 
-fn select_plan<'a>(
-    applicable_plans: &'a openfare_lib::lock::plan::Plans,
-) -> Option<(
-    &'a openfare_lib::lock::plan::Id,
-    &'a openfare_lib::lock::plan::Plan,
-)> {
-    let max_price: rust_decimal::Decimal = applicable_plans
-        .iter()
-        .map(|(_, plan)| {
-            if let Some(price) = &plan.price {
-                price.quantity
-            } else {
-                rust_decimal::Decimal::from(0)
-            }
-        })
-        .sum();
-    applicable_plans
+/// Returns the type of the given selected plans. Compulsory if any plan is compulsory.
+fn get_plan_type(
+    plans: &openfare_lib::lock::plan::Plans,
+) -> Option<openfare_lib::lock::plan::PlanType> {
+    plans
+        .values()
+        .map(|plan| plan.r#type.clone())
+        .min_by_key(|plan_type| *plan_type != openfare_lib::lock::plan::PlanType::Compulsory)
+}
+
+/// Returns notes explaining which plans were skipped because their conditions could not be
+/// evaluated.
+fn get_undecidable_notes(filtered_plans: &openfare_lib::lock::plan::FilteredPlans) -> Vec<String> {
+    filtered_plans
+        .undecidable
         .iter()
-        .filter(|(_, plan)| {
-            if let Some(price) = &plan.price {
-                price.quantity == max_price
-            } else {
-                false
-            }
+        .map(|(plan_id, reason)| {
+            format!(
+                "Plan {plan_id} skipped: {reason}",
+                plan_id = plan_id,
+                reason = reason
+            )
         })
-        .next()
+        .collect()
+}
+
+/// Returns a note giving the price breakdown of a per-unit plan.
+fn get_unit_price_note(
+    plan_id: &openfare_lib::lock::plan::Id,
+    plan: &openfare_lib::lock::plan::Plan,
+    total_price: &openfare_lib::price::Price,
+    parameters: &openfare_lib::lock::plan::conditions::Parameters,
+) -> Option<String> {
+    let unit = plan.unit.as_ref()?;
+    let unit_price = plan.price.as_ref()?;
+    let count = parameters.units.get(&unit)?;
+    Some(format!(
+        "Plan {plan_id}: {units} x {unit_price} per {unit} ({count} counted) = {total_price}",
+        plan_id = plan_id,
+        units = unit.units(&count).ok()?,
+        unit_price = unit_price.to_symbolic(),
+        unit = unit.to_string(),
+        count = count,
+        total_price = total_price.to_symbolic()
+    ))
+}
+
+/// Normalizes a recurring plan price to the given billing period. Returns a note describing
+/// the conversion if the price was modified.
+fn normalize_price(
+    plan_id: &openfare_lib::lock::plan::Id,
+    plan: &openfare_lib::lock::plan::Plan,
+    price: &mut openfare_lib::price::Price,
+    billing_period: &openfare_lib::lock::plan::billing_period::BillingPeriod,
+) -> Option<String> {
+    let plan_billing_period = plan.billing_period.clone().unwrap_or_default();
+    if plan_billing_period == openfare_lib::lock::plan::billing_period::BillingPeriod::OneOff {
+        return Some(format!(
+            "Plan {plan_id}: one-off price {price} not included in {billing_period} price",
+            plan_id = plan_id,
+            price = price.to_symbolic(),
+            billing_period = billing_period.to_string()
+        ));
+    }
+    if plan_billing_period == *billing_period {
+        return None;
+    }
+
+    let original_price = price.clone();
+    price.quantity = plan_billing_period.normalize(&price.quantity, &billing_period);
+    Some(format!(
+        "Plan {plan_id}: {original_price} {plan_billing_period} = {price} {billing_period}",
+        plan_id = plan_id,
+        original_price = original_price.to_symbolic(),
+        plan_billing_period = plan_billing_period.to_string(),
+        price = price.to_symbolic(),
+        billing_period = billing_period.to_string()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::price::tests::package;
+    use std::str::FromStr;
+
+    fn lock(
+        price: &str,
+        billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    ) -> Result<openfare_lib::lock::Lock> {
+        let mut lock = openfare_lib::lock::Lock::default();
+        lock.plans.insert(
+            "0".to_string(),
+            openfare_lib::lock::plan::Plan {
+                r#type: openfare_lib::lock::plan::PlanType::Compulsory,
+                conditions: Default::default(),
+                price: Some(openfare_lib::price::Price::from_str(price)?),
+                unit: None,
+                billing_period,
+                priority: None,
+            },
+        );
+        Ok(lock)
+    }
+
+    #[test]
+    fn test_one_off_prices_excluded_from_normalized_total() -> Result<()> {
+        let mut package_locks = openfare_lib::package::PackageLocks::default();
+        package_locks
+            .dependencies_locks
+            .insert(package("a", "1.0.0"), Some(lock("120 USD", None)?));
+        package_locks.dependencies_locks.insert(
+            package("b", "1.0.0"),
+            Some(lock(
+                "10 USD",
+                Some(openfare_lib::lock::plan::billing_period::BillingPeriod::Monthly),
+            )?),
+        );
+        let billing_period = Some(openfare_lib::lock::plan::billing_period::BillingPeriod::Yearly);
+        let price_report = get_report(
+            &package_locks,
+            &billing_period,
+            &openfare_lib::price::FixedRate(30000.into()),
+            &crate::config::Config::default(),
+        )?
+        .unwrap();
+
+        assert_eq!(
+            price_report.price,
+            openfare_lib::price::Price::from_str("120 USD")?
+        );
+        assert_eq!(
+            price_report.one_off_price,
+            Some(openfare_lib::price::Price::from_str("120 USD")?)
+        );
+        assert_eq!(
+            price_report.package_reports[0].price_quantity,
+            Some(rust_decimal::Decimal::from(0))
+        );
+
+        let price_report = get_report(
+            &package_locks,
+            &None,
+            &openfare_lib::price::FixedRate(30000.into()),
+            &crate::config::Config::default(),
+        )?
+        .unwrap();
+        assert_eq!(
+            price_report.price,
+            openfare_lib::price::Price::from_str("130 USD")?
+        );
+        assert_eq!(price_report.one_off_price, None);
+        Ok(())
+    }
 }
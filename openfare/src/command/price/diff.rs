@@ -0,0 +1,427 @@
+use crate::common::fs::FileStore;
+use anyhow::Result;
+use structopt::{self, StructOpt};
+
+use super::{common, format, project};
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Old price report source. A JSON price report file, a project directory or a git revision
+    /// of the current project.
+    /// Example: HEAD~1
+    pub old: String,
+
+    /// New price report source. Defaults to the current project directory.
+    #[structopt(default_value = ".")]
+    pub new: String,
+}
+
+pub fn run_command(
+    args: &Arguments,
+    extensions: &Vec<Box<dyn openfare_lib::extension::Extension>>,
+    extension_args: &Vec<String>,
+    dependency_kinds: &Vec<openfare_lib::package::DependencyKind>,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+    config: &mut crate::config::Config,
+) -> Result<()> {
+    let mut get_report = |source: &str| {
+        get_source_report(
+            &source,
+            &extensions,
+            &extension_args,
+            &dependency_kinds,
+            &billing_period,
+            rate_provider,
+            config,
+        )
+    };
+    let old_report = get_report(&args.old)?;
+    let new_report = get_report(&args.new)?;
+
+    for line in get_lines(
+        &old_report,
+        &new_report,
+        &config.core.preferred_currency,
+        rate_provider,
+    )? {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Returns the price report given by a source argument: a JSON price report file, a project
+/// directory or a git revision of the current project.
+fn get_source_report(
+    source: &str,
+    extensions: &Vec<Box<dyn openfare_lib::extension::Extension>>,
+    extension_args: &Vec<String>,
+    dependency_kinds: &Vec<openfare_lib::package::DependencyKind>,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+    config: &mut crate::config::Config,
+) -> Result<common::PriceReport> {
+    let path = std::path::PathBuf::from(source);
+    if path.is_file() {
+        log::debug!("Reading price report file: {}", path.display());
+        let report = std::fs::read_to_string(&path)?;
+        return format::json::parse(&report).map_err(|error| {
+            anyhow::format_err!(
+                "Failed to parse price report file {}: {}",
+                path.display(),
+                error
+            )
+        });
+    }
+
+    let mut get_directory_report = |directory: &std::path::PathBuf| {
+        get_directory_report(
+            &directory,
+            &extensions,
+            &extension_args,
+            &dependency_kinds,
+            &billing_period,
+            rate_provider,
+            config,
+        )
+    };
+    if path.is_dir() {
+        return get_directory_report(&path.canonicalize()?);
+    }
+
+    // Check out the git revision into a temporary worktree.
+    let working_directory = std::env::current_dir()?;
+    let output =
+        crate::common::git::run_command(vec!["rev-parse", "--show-prefix"], &working_directory)
+            .map_err(|_| {
+                anyhow::format_err!(
+                    "Price report source is not a file, directory or git revision: {}",
+                    source
+                )
+            })?;
+    let prefix = String::from_utf8(output.stdout)?.trim().to_string();
+
+    let tmp_dir = tempdir::TempDir::new("openfare_price_diff")?;
+    let worktree_path = tmp_dir.path().join("worktree");
+    let worktree_path_str = worktree_path.to_string_lossy().to_string();
+    crate::common::git::run_command(
+        vec!["worktree", "add", "--detach", &worktree_path_str, source],
+        &working_directory,
+    )?;
+    let report = get_directory_report(&worktree_path.join(prefix));
+    crate::common::git::run_command(
+        vec!["worktree", "remove", "--force", &worktree_path_str],
+        &working_directory,
+    )?;
+    report
+}
+
+/// Generates a price report for the project in the given directory. Reports from each
+/// extension are combined.
+fn get_directory_report(
+    directory: &std::path::PathBuf,
+    extensions: &Vec<Box<dyn openfare_lib::extension::Extension>>,
+    extension_args: &Vec<String>,
+    dependency_kinds: &Vec<openfare_lib::package::DependencyKind>,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+    config: &mut crate::config::Config,
+) -> Result<common::PriceReport> {
+    let extensions_results = project::query_extensions(&directory, &extensions, &extension_args)?;
+    for (_extension, result) in extensions_results {
+        if !openfare_lib::lock::plan::conditions::parameters::check_set(
+            &result.package_locks.conditions_metadata(),
+            &mut config.profile.parameters,
+        )? {
+            config.dump()?;
+        }
+    }
+
+    let project_reports = project::get_reports(
+        &directory,
+        &extensions,
+        &extension_args,
+        &dependency_kinds,
+        &billing_period,
+        rate_provider,
+        &config,
+    )?;
+    let price_reports = project_reports
+        .into_iter()
+        .filter_map(|project_report| project_report.price_report)
+        .collect::<Vec<_>>();
+    common::combine(
+        &price_reports,
+        &config.core.preferred_currency,
+        &billing_period,
+        rate_provider,
+    )
+}
+
+/// Returns lines describing added (+), removed (-) and changed (~) packages followed by the
+/// change in total price. Prices are converted into the given currency.
+fn get_lines(
+    old_report: &common::PriceReport,
+    new_report: &common::PriceReport,
+    currency: &openfare_lib::price::Currency,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+) -> Result<Vec<String>> {
+    let old_packages = get_packages(&old_report, &currency, rate_provider)?;
+    let new_packages = get_packages(&new_report, &currency, rate_provider)?;
+    let version_changes = get_version_changes(&old_packages, &new_packages);
+
+    let mut lines = vec![];
+    for (key, (old_package_report, old_price)) in &old_packages {
+        if !new_packages.contains_key(key) && !version_changes.values().any(|old| old == key) {
+            lines.push(format!(
+                "- {package}: {plan} {price}",
+                package = get_package_label(&old_package_report.package),
+                plan = get_plan_label(&old_package_report),
+                price = old_price
+            ));
+        }
+    }
+    for (key, (new_package_report, new_price)) in &new_packages {
+        let old_package = old_packages.get(key).or_else(|| {
+            version_changes
+                .get(key)
+                .and_then(|old_key| old_packages.get(old_key))
+        });
+        let (old_package_report, old_price) = match old_package {
+            Some(old_package) => old_package,
+            None => {
+                lines.push(format!(
+                    "+ {package}: {plan} {price}",
+                    package = get_package_label(&new_package_report.package),
+                    plan = get_plan_label(&new_package_report),
+                    price = new_price
+                ));
+                continue;
+            }
+        };
+
+        let mut changes = vec![];
+        let old_plan = get_plan_label(&old_package_report);
+        let new_plan = get_plan_label(&new_package_report);
+        if old_plan != new_plan {
+            changes.push(format!("{} -> {}", old_plan, new_plan));
+        }
+        if old_price != new_price {
+            changes.push(format!(
+                "{old_price} -> {new_price} ({delta})",
+                old_price = old_price,
+                new_price = new_price,
+                delta = get_delta(&old_price, &new_price)
+            ));
+        }
+        let old_version = &old_package_report.package.version;
+        let new_version = &new_package_report.package.version;
+        if changes.is_empty() && old_version == new_version {
+            continue;
+        }
+        let package = if old_version == new_version {
+            get_package_label(&new_package_report.package)
+        } else {
+            format!(
+                "{package} -> {new_version}",
+                package = get_package_label(&old_package_report.package),
+                new_version = new_version
+            )
+        };
+        if changes.is_empty() {
+            lines.push(format!("~ {package}", package = package));
+        } else {
+            lines.push(format!(
+                "~ {package}: {changes}",
+                package = package,
+                changes = changes.join(", ")
+            ));
+        }
+    }
+
+    let old_total = old_report.price.to(&currency, rate_provider)?;
+    let new_total = new_report.price.to(&currency, rate_provider)?;
+    lines.push(format!(
+        "Total: {old_total} -> {new_total} ({delta})",
+        old_total = old_total,
+        new_total = new_total,
+        delta = get_delta(&old_total, &new_total)
+    ));
+    Ok(lines)
+}
+
+type PackageKey<'a> = (&'a str, &'a str, &'a str);
+
+/// Returns package reports and prices in the given currency keyed by registry, name and version.
+fn get_packages<'a>(
+    price_report: &'a common::PriceReport,
+    currency: &openfare_lib::price::Currency,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+) -> Result<
+    std::collections::BTreeMap<
+        PackageKey<'a>,
+        (&'a common::PackagePriceReport, openfare_lib::price::Price),
+    >,
+> {
+    let mut packages = std::collections::BTreeMap::new();
+    for package_report in &price_report.package_reports {
+        let price = openfare_lib::price::Price {
+            quantity: package_report.price_quantity.unwrap_or_default(),
+            currency: price_report.price.currency.clone(),
+        }
+        .to(&currency, rate_provider)?;
+        packages.insert(
+            (
+                package_report.package.registry.as_str(),
+                package_report.package.name.as_str(),
+                package_report.package.version.as_str(),
+            ),
+            (package_report, price),
+        );
+    }
+    Ok(packages)
+}
+
+/// Pairs package versions which are only found in the old report with versions of the same
+/// package which are only found in the new report. Versions are paired in order.
+///
+/// Returns the old package key of each paired new package key.
+fn get_version_changes<'a, T>(
+    old_packages: &std::collections::BTreeMap<PackageKey<'a>, T>,
+    new_packages: &std::collections::BTreeMap<PackageKey<'a>, T>,
+) -> std::collections::BTreeMap<PackageKey<'a>, PackageKey<'a>> {
+    let mut removed = std::collections::BTreeMap::<_, std::collections::VecDeque<_>>::new();
+    for key in old_packages.keys() {
+        if !new_packages.contains_key(key) {
+            let (registry, name, _version) = key;
+            removed
+                .entry((*registry, *name))
+                .or_default()
+                .push_back(key.clone());
+        }
+    }
+
+    let mut version_changes = std::collections::BTreeMap::new();
+    for key in new_packages.keys() {
+        if old_packages.contains_key(key) {
+            continue;
+        }
+        let (registry, name, _version) = key;
+        if let Some(old_key) = removed
+            .get_mut(&(*registry, *name))
+            .and_then(|old_keys| old_keys.pop_front())
+        {
+            version_changes.insert(key.clone(), old_key);
+        }
+    }
+    version_changes
+}
+
+fn get_package_label(package: &openfare_lib::package::Package) -> String {
+    format!(
+        "{registry} {name} {version}",
+        registry = package.registry,
+        name = package.name,
+        version = package.version
+    )
+}
+
+fn get_plan_label(package_report: &common::PackagePriceReport) -> String {
+    let plans = match package_report.plan_ids.len() {
+        0 => return "no plan".to_string(),
+        1 => "plan",
+        _ => "plans",
+    };
+    let plan_ids = package_report.plan_ids.join(", ");
+    match &package_report.plan_type {
+        Some(plan_type) => format!(
+            "{plans} {plan_ids} ({plan_type})",
+            plans = plans,
+            plan_ids = plan_ids,
+            plan_type = plan_type
+        ),
+        None => format!("{plans} {plan_ids}", plans = plans, plan_ids = plan_ids),
+    }
+}
+
+/// Returns the signed difference between two prices of the same currency.
+fn get_delta(
+    old_price: &openfare_lib::price::Price,
+    new_price: &openfare_lib::price::Price,
+) -> String {
+    let delta = openfare_lib::price::Price {
+        quantity: new_price.quantity - old_price.quantity,
+        currency: new_price.currency.clone(),
+    };
+    if delta.quantity.is_sign_negative() {
+        delta.to_string()
+    } else {
+        format!("+{}", delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::price::tests::price_report;
+
+    fn package_report(
+        name: &str,
+        version: &str,
+        price_quantity: i64,
+    ) -> common::PackagePriceReport {
+        crate::command::price::tests::package_report(
+            name,
+            version,
+            openfare_lib::lock::plan::PlanType::Compulsory,
+            price_quantity,
+        )
+    }
+
+    #[test]
+    fn test_get_lines() -> Result<()> {
+        let old_report = price_report(
+            vec![
+                package_report("a", "1.0.0", 10),
+                package_report("b", "1.0.0", 5),
+                package_report("c", "1.0.0", 0),
+                package_report("e", "1.0.0", 4),
+                package_report("e", "2.0.0", 6),
+            ],
+            "25 USD",
+        )?;
+        let new_report = price_report(
+            vec![
+                package_report("a", "2.0.0", 12),
+                package_report("c", "1.0.0", 0),
+                package_report("d", "1.0.0", 3),
+                package_report("e", "2.0.0", 6),
+                package_report("e", "3.0.0", 4),
+            ],
+            "25 USD",
+        )?;
+
+        let lines = get_lines(
+            &old_report,
+            &new_report,
+            &openfare_lib::price::Currency::USD,
+            &openfare_lib::price::FixedRate(30000.into()),
+        )?;
+        assert_eq!(
+            lines,
+            vec![
+                "- npmjs.com b 1.0.0: plan 0 (compulsory) 5.00 USD",
+                "~ npmjs.com a 1.0.0 -> 2.0.0: 10.00 USD -> 12.00 USD (+2.00 USD)",
+                "+ npmjs.com d 1.0.0: plan 0 (compulsory) 3.00 USD",
+                "~ npmjs.com e 1.0.0 -> 3.0.0",
+                "Total: 25.00 USD -> 25.00 USD (+0.00 USD)",
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+
+/// Generates CSV from a given price report. One record per package.
+pub fn get(price_report: &super::common::PriceReport) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&[
+        "name",
+        "version",
+        "registry",
+        "plan-ids",
+        "plan-type",
+        "price",
+        "currency",
+        "notes",
+        "payees",
+    ])?;
+    for row in super::get_rows(&price_report) {
+        writer.write_record(&[
+            row.name,
+            row.version,
+            row.registry,
+            row.plan_ids.join(";"),
+            row.plan_type
+                .map(|plan_type| plan_type.to_string())
+                .unwrap_or_default(),
+            row.price.map(|price| price.to_string()).unwrap_or_default(),
+            row.currency.to_string(),
+            row.notes.join("\n"),
+            row.payees.join(";"),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Generates CSV from the payee-centric view of a given price report. One record per payee.
+pub fn get_payees(price_report: &super::common::PriceReport) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&["payee", "unique-id", "price", "currency", "packages"])?;
+    for row in super::get_payee_rows(&price_report) {
+        writer.write_record(&[
+            row.payee,
+            row.unique_id.to_string(),
+            row.price.to_string(),
+            row.currency.to_string(),
+            row.packages.join("\n"),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
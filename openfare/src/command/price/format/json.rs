@@ -0,0 +1,79 @@
+use anyhow::Result;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Report {
+    price: openfare_lib::price::Price,
+    #[serde(
+        rename = "billing-period",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    #[serde(
+        rename = "one-off-price",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    one_off_price: Option<openfare_lib::price::Price>,
+    packages: Vec<super::Row>,
+}
+
+/// Generates a JSON document from a given price report.
+pub fn get(price_report: &super::common::PriceReport) -> Result<String> {
+    let report = Report {
+        price: price_report.price.clone(),
+        billing_period: price_report.billing_period.clone(),
+        one_off_price: price_report.one_off_price.clone(),
+        packages: super::get_rows(&price_report),
+    };
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+/// Parses a price report from a JSON document generated by `get`. Payee reports are not
+/// included.
+pub fn parse(value: &str) -> Result<super::common::PriceReport> {
+    let report: Report = serde_json::from_str(&value)?;
+    Ok(super::common::PriceReport {
+        package_reports: report
+            .packages
+            .into_iter()
+            .map(|row| super::common::PackagePriceReport {
+                package: openfare_lib::package::Package {
+                    registry: row.registry,
+                    name: row.name,
+                    version: row.version,
+                },
+                plan_ids: row.plan_ids,
+                plan_type: row.plan_type,
+                price_quantity: row.price,
+                compulsory_price_quantity: row.compulsory_price,
+                notes: row.notes,
+                payees: row.payees,
+            })
+            .collect(),
+        payee_reports: vec![],
+        price: report.price,
+        billing_period: report.billing_period,
+        one_off_price: report.one_off_price,
+    })
+}
+
+/// Generates a JSON document from the payee-centric view of a given price report.
+pub fn get_payees(price_report: &super::common::PriceReport) -> Result<String> {
+    #[derive(serde::Serialize)]
+    struct Report {
+        price: openfare_lib::price::Price,
+        #[serde(rename = "billing-period", skip_serializing_if = "Option::is_none")]
+        billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+        #[serde(rename = "one-off-price", skip_serializing_if = "Option::is_none")]
+        one_off_price: Option<openfare_lib::price::Price>,
+        payees: Vec<super::PayeeRow>,
+    }
+    let report = Report {
+        price: price_report.price.clone(),
+        billing_period: price_report.billing_period.clone(),
+        one_off_price: price_report.one_off_price.clone(),
+        payees: super::get_payee_rows(&price_report),
+    };
+    Ok(serde_json::to_string_pretty(&report)?)
+}
@@ -0,0 +1,58 @@
+/// Generates a Markdown table from a given price report.
+pub fn get(price_report: &super::common::PriceReport) -> String {
+    let mut lines = vec![
+        format!(
+            "| name | version | registry | plans | type | price ({currency}) | notes | payees |",
+            currency = price_report.price.currency.to_string()
+        ),
+        "| --- | --- | --- | --- | --- | ---: | --- | --- |".to_string(),
+    ];
+    for row in super::get_rows(&price_report) {
+        let cells = vec![
+            row.name,
+            row.version,
+            row.registry,
+            row.plan_ids.join(", "),
+            row.plan_type
+                .map(|plan_type| plan_type.to_string())
+                .unwrap_or_default(),
+            row.price
+                .map(|price| super::display_quantity(&price, &row.currency))
+                .unwrap_or("-".to_string()),
+            row.notes.join("<br>"),
+            row.payees.join(", "),
+        ];
+        let cells = cells.iter().map(|cell| escape(&cell)).collect::<Vec<_>>();
+        lines.push(format!("| {} |", cells.join(" | ")));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Generates a Markdown table from the payee-centric view of a given price report.
+pub fn get_payees(price_report: &super::common::PriceReport) -> String {
+    let mut lines = vec![
+        format!(
+            "| payee | unique id | price ({currency}) | packages |",
+            currency = price_report.price.currency.to_string()
+        ),
+        "| --- | --- | ---: | --- |".to_string(),
+    ];
+    for row in super::get_payee_rows(&price_report) {
+        let cells = vec![
+            row.payee,
+            row.unique_id.to_string(),
+            super::display_quantity(&row.price, &row.currency),
+            row.packages.join("<br>"),
+        ];
+        let cells = cells.iter().map(|cell| escape(&cell)).collect::<Vec<_>>();
+        lines.push(format!("| {} |", cells.join(" | ")));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Escapes characters which would break a Markdown table cell.
+fn escape(cell: &str) -> String {
+    cell.replace("|", "\\|").replace("\n", "<br>")
+}
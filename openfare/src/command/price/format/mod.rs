@@ -1,9 +1,68 @@
 use super::common;
 use anyhow::Result;
+mod csv;
+pub mod json;
+mod markdown;
 mod table;
+mod tree;
 
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Format {
     Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> std::result::Result<Self, anyhow::Error> {
+        Ok(match value {
+            "table" => Format::Table,
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            "markdown" => Format::Markdown,
+            _ => {
+                return Err(anyhow::format_err!(
+                    "Unsupported format: {}. Supported values: [table|json|csv|markdown].",
+                    value
+                ));
+            }
+        })
+    }
+}
+
+/// Arrangement of a price report.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum View {
+    /// One row per package.
+    Packages,
+    /// One row per payee.
+    Payees,
+    /// Dependency tree. Table format only.
+    Tree,
+}
+
+/// Prints a report in the given format and view.
+pub fn print_report(
+    report: &common::PriceReport,
+    package_locks: &openfare_lib::package::PackageLocks,
+    format: &Format,
+    view: &View,
+) -> Result<()> {
+    match view {
+        View::Packages => print(&report, &format, true),
+        View::Payees => print_payees(&report, &format),
+        View::Tree => {
+            if *format != Format::Table {
+                return Err(anyhow::format_err!(
+                    "Dependency tree view only supports the table format."
+                ));
+            }
+            println!("{}", tree::get(&report, &package_locks));
+            Ok(())
+        }
+    }
 }
 
 pub fn print(
@@ -16,6 +75,113 @@ pub fn print(
             let table = table::get(&report, first_row_separate)?;
             table.printstd();
         }
+        Format::Json => println!("{}", json::get(&report)?),
+        Format::Csv => print!("{}", csv::get(&report)?),
+        Format::Markdown => print!("{}", markdown::get(&report)),
     }
     Ok(())
 }
+
+/// Prints the payee-centric view of a price report.
+pub fn print_payees(report: &common::PriceReport, format: &Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let table = table::get_payees(&report)?;
+            table.printstd();
+        }
+        Format::Json => println!("{}", json::get_payees(&report)?),
+        Format::Csv => print!("{}", csv::get_payees(&report)?),
+        Format::Markdown => print!("{}", markdown::get_payees(&report)),
+    }
+    Ok(())
+}
+
+/// Formats a price quantity of the given currency for display in human-readable formats.
+fn display_quantity(
+    quantity: &openfare_lib::price::Quantity,
+    currency: &openfare_lib::price::Currency,
+) -> String {
+    quantity
+        .round_dp(currency.display_decimal_points())
+        .to_string()
+}
+
+/// Package price report fields as presented in machine-readable formats.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Row {
+    name: String,
+    version: String,
+    registry: String,
+    #[serde(rename = "plan-ids")]
+    plan_ids: Vec<openfare_lib::lock::plan::Id>,
+    #[serde(rename = "plan-type")]
+    plan_type: Option<openfare_lib::lock::plan::PlanType>,
+    price: Option<openfare_lib::price::Quantity>,
+    #[serde(
+        rename = "compulsory-price",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    compulsory_price: Option<openfare_lib::price::Quantity>,
+    currency: openfare_lib::price::Currency,
+    notes: Vec<String>,
+    payees: Vec<openfare_lib::lock::payee::Label>,
+}
+
+fn get_rows(price_report: &common::PriceReport) -> Vec<Row> {
+    price_report
+        .package_reports
+        .iter()
+        .map(|report| Row {
+            name: report.package.name.clone(),
+            version: report.package.version.clone(),
+            registry: report.package.registry.clone(),
+            plan_ids: report.plan_ids.clone(),
+            plan_type: report.plan_type.clone(),
+            price: report.price_quantity.clone(),
+            compulsory_price: report.compulsory_price_quantity.clone(),
+            currency: price_report.price.currency.clone(),
+            notes: report.notes.clone(),
+            payees: report.payees.clone(),
+        })
+        .collect()
+}
+
+/// Payee price report fields as presented in machine-readable formats.
+#[derive(Debug, Clone, serde::Serialize)]
+struct PayeeRow {
+    payee: openfare_lib::lock::payee::Label,
+    #[serde(rename = "unique-id")]
+    unique_id: uuid::Uuid,
+    price: openfare_lib::price::Quantity,
+    currency: openfare_lib::price::Currency,
+
+    /// Contributing packages. Example: "left-pad 1.3.0: 2.50"
+    packages: Vec<String>,
+}
+
+fn get_payee_rows(price_report: &common::PriceReport) -> Vec<PayeeRow> {
+    price_report
+        .payee_reports
+        .iter()
+        .map(|report| PayeeRow {
+            payee: report.label.clone(),
+            unique_id: report.unique_id,
+            price: report.price_quantity,
+            currency: price_report.price.currency.clone(),
+            packages: report
+                .packages
+                .iter()
+                .map(|(package, price_quantity)| {
+                    format!(
+                        "{name} {version}: {price_quantity}",
+                        name = package.name,
+                        version = package.version,
+                        price_quantity =
+                            display_quantity(&price_quantity, &price_report.price.currency)
+                    )
+                })
+                .collect(),
+        })
+        .collect()
+}
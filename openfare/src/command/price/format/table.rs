@@ -18,23 +18,26 @@ pub fn get(
     let mut reports_iter = price_report.package_reports.iter();
     if first_row_separate {
         if let Some(report) = reports_iter.next() {
-            let row = get_row(&report);
+            let row = get_row(&report, &price_report.price.currency);
             table.add_row(row);
             table.add_row(prettytable::row![c => "", "", "", ""]);
         }
     }
 
     for report in reports_iter {
-        let row = get_row(&report);
+        let row = get_row(&report, &price_report.price.currency);
         table.add_row(row);
     }
     Ok(table)
 }
 
-fn get_row(report: &super::common::PackagePriceReport) -> prettytable::Row {
+fn get_row(
+    report: &super::common::PackagePriceReport,
+    currency: &openfare_lib::price::Currency,
+) -> prettytable::Row {
     let price = report
         .price_quantity
-        .map(|p| p.to_string())
+        .map(|p| super::display_quantity(&p, &currency))
         .unwrap_or("-".to_string());
     prettytable::Row::new(vec![
         prettytable::Cell::new_align(&report.package.name, prettytable::format::Alignment::LEFT),
@@ -43,5 +46,35 @@ fn get_row(report: &super::common::PackagePriceReport) -> prettytable::Row {
             prettytable::format::Alignment::LEFT,
         ),
         prettytable::Cell::new_align(&price, prettytable::format::Alignment::CENTER),
+        prettytable::Cell::new_align(
+            &report.notes.join("\n"),
+            prettytable::format::Alignment::LEFT,
+        ),
     ])
 }
+
+/// Generates and returns a table from the payee-centric view of a given price report.
+pub fn get_payees(price_report: &super::common::PriceReport) -> Result<prettytable::Table> {
+    let mut table = prettytable::Table::new();
+    table.set_titles(prettytable::row![c =>
+        "payee",
+        format!("price ({})", price_report.price.currency.to_string()),
+        "packages",
+    ]);
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+
+    for row in super::get_payee_rows(&price_report) {
+        table.add_row(prettytable::Row::new(vec![
+            prettytable::Cell::new_align(&row.payee, prettytable::format::Alignment::LEFT),
+            prettytable::Cell::new_align(
+                &super::display_quantity(&row.price, &row.currency),
+                prettytable::format::Alignment::CENTER,
+            ),
+            prettytable::Cell::new_align(
+                &row.packages.join("\n"),
+                prettytable::format::Alignment::LEFT,
+            ),
+        ]));
+    }
+    Ok(table)
+}
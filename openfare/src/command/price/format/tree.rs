@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Generates a dependency tree from a given price report. Each package is annotated with its
+/// price and, where it has dependencies, the total price of its subtree. Paid transitive
+/// dependencies are marked with the direct dependency which pulls them in.
+///
+/// Packages which are already shown elsewhere in the tree are marked (*) and not expanded again.
+pub fn get(
+    price_report: &super::common::PriceReport,
+    package_locks: &openfare_lib::package::PackageLocks,
+) -> String {
+    let tree = Tree {
+        prices: price_report
+            .package_reports
+            .iter()
+            .map(|report| (&report.package, report.price_quantity.unwrap_or_default()))
+            .collect(),
+        package_locks,
+        currency: &price_report.price.currency,
+    };
+
+    let mut lines = vec![];
+    let mut expanded = BTreeSet::new();
+    let direct_dependencies = package_locks.direct_dependencies();
+    let mut prefix = "";
+    if let Some(primary_package) = &package_locks.primary_package {
+        let mut subtree = BTreeSet::new();
+        subtree.insert(primary_package);
+        for package in &direct_dependencies {
+            subtree.extend(tree.subtree(package));
+        }
+        let mut line = tree.label(&primary_package);
+        let price_quantity = tree
+            .prices
+            .get(primary_package)
+            .cloned()
+            .unwrap_or_default();
+        if !price_quantity.is_zero() {
+            line.push_str(&format!(" {}", tree.price(price_quantity).to_symbolic()));
+        }
+        line.push_str(&format!(" [subtree: {}]", tree.total(&subtree)));
+        lines.push(line);
+        prefix = " ";
+    }
+    for (index, package) in direct_dependencies.iter().enumerate() {
+        tree.render(
+            package,
+            prefix,
+            index + 1 == direct_dependencies.len(),
+            None,
+            &mut expanded,
+            &mut lines,
+        );
+    }
+    lines.join("\n")
+}
+
+struct Tree<'a> {
+    prices: BTreeMap<&'a openfare_lib::package::Package, openfare_lib::price::Quantity>,
+    package_locks: &'a openfare_lib::package::PackageLocks,
+    currency: &'a openfare_lib::price::Currency,
+}
+
+impl<'a> Tree<'a> {
+    fn render(
+        &self,
+        package: &'a openfare_lib::package::Package,
+        prefix: &str,
+        is_last: bool,
+        direct_dependency: Option<&'a openfare_lib::package::Package>,
+        expanded: &mut BTreeSet<&'a openfare_lib::package::Package>,
+        lines: &mut Vec<String>,
+    ) {
+        let children = self.package_locks.child_dependencies(&package);
+        let is_expanded = expanded.contains(package);
+
+        let mut line = format!(
+            "{prefix}{branch}{package}",
+            prefix = prefix,
+            branch = if is_last { "└── " } else { "├── " },
+            package = self.label(&package)
+        );
+        let price_quantity = self.prices.get(package).cloned().unwrap_or_default();
+        if !price_quantity.is_zero() {
+            line.push_str(&format!(" {}", self.price(price_quantity).to_symbolic()));
+            if let Some(direct_dependency) = direct_dependency {
+                line.push_str(&format!(" (via {name})", name = direct_dependency.name));
+            }
+        }
+        if !children.is_empty() {
+            line.push_str(&format!(
+                " [subtree: {total}]",
+                total = self.total(&self.subtree(&package))
+            ));
+        }
+        if is_expanded && !children.is_empty() {
+            line.push_str(" (*)");
+        }
+        lines.push(line);
+
+        if is_expanded {
+            return;
+        }
+        expanded.insert(package);
+
+        let prefix = format!(
+            "{prefix}{indent}",
+            prefix = prefix,
+            indent = if is_last { "    " } else { "│   " }
+        );
+        let direct_dependency = direct_dependency.unwrap_or(package);
+        for (index, child) in children.iter().enumerate() {
+            self.render(
+                child,
+                &prefix,
+                index + 1 == children.len(),
+                Some(direct_dependency),
+                expanded,
+                lines,
+            );
+        }
+    }
+
+    /// Returns the given package and all packages which it depends on, directly or otherwise.
+    fn subtree(
+        &self,
+        package: &'a openfare_lib::package::Package,
+    ) -> BTreeSet<&'a openfare_lib::package::Package> {
+        let mut subtree = BTreeSet::new();
+        let mut stack = vec![package];
+        while let Some(package) = stack.pop() {
+            if subtree.insert(package) {
+                stack.extend(self.package_locks.child_dependencies(&package));
+            }
+        }
+        subtree
+    }
+
+    /// Returns the total price of the given packages. Each package is counted once.
+    fn total(&self, packages: &BTreeSet<&'a openfare_lib::package::Package>) -> String {
+        let total = packages
+            .iter()
+            .map(|package| self.prices.get(package).cloned().unwrap_or_default())
+            .sum();
+        self.price(total).to_symbolic()
+    }
+
+    fn price(&self, quantity: openfare_lib::price::Quantity) -> openfare_lib::price::Price {
+        openfare_lib::price::Price {
+            quantity,
+            currency: self.currency.clone(),
+        }
+    }
+
+    fn label(&self, package: &openfare_lib::package::Package) -> String {
+        format!(
+            "{name} {version}",
+            name = package.name,
+            version = package.version
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::price::{common, tests as fixtures};
+    use anyhow::Result;
+
+    /// Primary package app depends on a and b. Both a and b depend on c which depends on d.
+    fn get_report() -> Result<(common::PriceReport, openfare_lib::package::PackageLocks)> {
+        let compulsory = openfare_lib::lock::plan::PlanType::Compulsory;
+        let price_report = fixtures::price_report(
+            vec![
+                fixtures::package_report("a", "1.0.0", compulsory.clone(), 10),
+                fixtures::package_report("b", "1.0.0", compulsory.clone(), 0),
+                fixtures::package_report("c", "1.0.0", compulsory.clone(), 5),
+                fixtures::package_report("d", "1.0.0", compulsory.clone(), 2),
+            ],
+            "17 USD",
+        )?;
+        let package =
+            |name: &str| fixtures::package_report(name, "1.0.0", compulsory.clone(), 0).package;
+
+        let mut package_locks = openfare_lib::package::PackageLocks::default();
+        package_locks.primary_package = Some(package("app"));
+        for name in ["a", "b", "c", "d"] {
+            package_locks.dependencies_locks.insert(package(name), None);
+        }
+        let edges = [
+            ("app", "a"),
+            ("app", "b"),
+            ("a", "c"),
+            ("b", "c"),
+            ("c", "d"),
+        ];
+        for (parent, child) in edges {
+            package_locks
+                .dependencies_edges
+                .entry(package(parent))
+                .or_default()
+                .insert(package(child));
+        }
+        Ok((price_report, package_locks))
+    }
+
+    #[test]
+    fn test_get() -> Result<()> {
+        let (price_report, package_locks) = get_report()?;
+        let tree = get(&price_report, &package_locks);
+        assert_eq!(
+            tree.lines().collect::<Vec<_>>(),
+            vec![
+                "app 1.0.0 [subtree: $17.00]",
+                " ├── a 1.0.0 $10.00 [subtree: $17.00]",
+                " │   └── c 1.0.0 $5.00 (via a) [subtree: $7.00]",
+                " │       └── d 1.0.0 $2.00 (via a)",
+                " └── b 1.0.0 [subtree: $7.00]",
+                "     └── c 1.0.0 $5.00 (via b) [subtree: $7.00] (*)",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_subtree_counts_shared_dependencies_once() -> Result<()> {
+        let (price_report, package_locks) = get_report()?;
+        let tree = Tree {
+            prices: price_report
+                .package_reports
+                .iter()
+                .map(|report| (&report.package, report.price_quantity.unwrap_or_default()))
+                .collect(),
+            package_locks: &package_locks,
+            currency: &price_report.price.currency,
+        };
+        let primary_package = package_locks.primary_package.as_ref().unwrap();
+        let subtree = tree.subtree(primary_package);
+        assert_eq!(subtree.len(), 5);
+        assert_eq!(tree.total(&subtree), "$17.00");
+        Ok(())
+    }
+}
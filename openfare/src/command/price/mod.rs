@@ -4,10 +4,14 @@ use structopt::{self, StructOpt};
 
 use crate::extensions;
 
+mod budget;
 mod common;
+mod diff;
 mod format;
 mod package;
 mod project;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
@@ -28,14 +32,111 @@ pub struct Arguments {
     /// Example values: py, js, rs
     #[structopt(long = "extension", short = "e", name = "name")]
     pub extension_names: Option<Vec<String>>,
+
+    /// Evaluate plan conditions at the given date instead of today. Example: "2027-01-01"
+    #[structopt(long)]
+    pub date: Option<String>,
+
+    /// Only include dependencies of the given kinds. Example: "runtime,optional"
+    /// Values: runtime, dev, build, optional
+    #[structopt(long = "dependency-kind", use_delimiter = true)]
+    pub dependency_kinds: Vec<openfare_lib::package::DependencyKind>,
+
+    /// Normalize recurring prices to the given billing period. Example: "yearly"
+    /// Values: monthly, yearly
+    #[structopt(long = "billing-period", parse(try_from_str = parse_billing_period))]
+    pub billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+
+    /// Output format.
+    /// Values: table, json, csv, markdown
+    #[structopt(long, default_value = "table")]
+    pub format: format::Format,
+
+    /// Group prices by payee rather than by package.
+    #[structopt(long = "by-payee", conflicts_with = "tree")]
+    pub by_payee: bool,
+
+    /// Show the dependency tree with per-subtree totals.
+    #[structopt(long)]
+    pub tree: bool,
+
+    /// Check compulsory fees against the configured budget. Exits with an error if any limit
+    /// is exceeded.
+    #[structopt(long = "check-budget")]
+    pub check_budget: bool,
+
+    // SUBCOMMANDS
+    #[structopt(subcommand)]
+    subcommand: Option<Subcommands>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+enum Subcommands {
+    /// Compare two price reports.
+    Diff(diff::Arguments),
+}
+
+impl Arguments {
+    fn view(&self) -> format::View {
+        if self.by_payee {
+            format::View::Payees
+        } else if self.tree {
+            format::View::Tree
+        } else {
+            format::View::Packages
+        }
+    }
+}
+
+/// Parses a recurring billing period. Recurring prices can not be normalized to one-off prices.
+fn parse_billing_period(
+    value: &str,
+) -> Result<openfare_lib::lock::plan::billing_period::BillingPeriod> {
+    let billing_period =
+        value.parse::<openfare_lib::lock::plan::billing_period::BillingPeriod>()?;
+    if billing_period.months().is_none() {
+        return Err(anyhow::format_err!(
+            "Unsupported billing period: {}. Accepted values: monthly, yearly",
+            value
+        ));
+    }
+    Ok(billing_period)
 }
 
 pub fn run_command(args: &Arguments, extension_args: &Vec<String>) -> Result<()> {
+    if args.tree && args.format != format::Format::Table {
+        return Err(anyhow::format_err!(
+            "Dependency tree view only supports the table format."
+        ));
+    }
     let mut config = crate::config::Config::load()?;
     extensions::manage::update_config(&mut config)?;
+    if let Some(date) = &args.date {
+        config.profile.parameters.set_evaluation_date(&date)?;
+    }
     let extensions = extensions::manage::from_names_arg(&args.extension_names, &config)?;
+    let rates_snapshot = crate::rates::snapshot(&config)?;
+    let billing_period = if args.check_budget && args.billing_period.is_none() {
+        config.budget.billing_period.clone()
+    } else {
+        args.billing_period.clone()
+    };
+
+    if let Some(Subcommands::Diff(diff_args)) = &args.subcommand {
+        diff::run_command(
+            &diff_args,
+            &extensions,
+            &extension_args,
+            &args.dependency_kinds,
+            &billing_period,
+            &rates_snapshot,
+            &mut config,
+        )?;
+        crate::rates::store(&rates_snapshot, &config)?;
+        return Ok(());
+    }
 
-    match &args.package_name {
+    let price_reports = match &args.package_name {
         Some(package_name) => {
             let extensions_results = package::query_extensions(
                 &package_name,
@@ -56,11 +157,17 @@ pub fn run_command(args: &Arguments, extension_args: &Vec<String>) -> Result<()>
                 &args.package_version.as_deref(),
                 &extensions,
                 &extension_args,
+                &args.dependency_kinds,
+                &billing_period,
+                &args.format,
+                &args.view(),
+                &rates_snapshot,
                 &config,
-            )?;
+            )?
         }
         None => {
-            let extensions_results = project::query_extensions(&extensions, &extension_args)?;
+            let extensions_results =
+                project::query_extensions(&std::env::current_dir()?, &extensions, &extension_args)?;
             for (_extension, result) in extensions_results {
                 if !openfare_lib::lock::plan::conditions::parameters::check_set(
                     &result.package_locks.conditions_metadata(),
@@ -69,8 +176,29 @@ pub fn run_command(args: &Arguments, extension_args: &Vec<String>) -> Result<()>
                     config.dump()?;
                 }
             }
-            project::price(&extensions, &extension_args, &config)?;
+            project::price(
+                &extensions,
+                &extension_args,
+                &args.dependency_kinds,
+                &billing_period,
+                &args.format,
+                &args.view(),
+                &rates_snapshot,
+                &config,
+            )?
+        }
+    };
+    crate::rates::store(&rates_snapshot, &config)?;
+
+    if args.check_budget {
+        let exceeded = budget::check(&price_reports, &config.budget, &rates_snapshot)?;
+        if !exceeded.is_empty() {
+            for line in exceeded {
+                eprintln!("{}", line);
+            }
+            return Err(anyhow::format_err!("Compulsory fees exceed budget."));
         }
+        eprintln!("Compulsory fees within budget.");
     }
     Ok(())
 }
@@ -2,14 +2,19 @@ use super::{common, format};
 use crate::extensions;
 use anyhow::Result;
 
-/// Prints a price report for a specific package and its dependencies.
+/// Prints a price report for a specific package and its dependencies. Returns the reports.
 pub fn price(
     package_name: &str,
     package_version: &Option<&str>,
     extensions: &Vec<Box<dyn openfare_lib::extension::Extension>>,
     extension_args: &Vec<String>,
+    dependency_kinds: &Vec<openfare_lib::package::DependencyKind>,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    format: &format::Format,
+    view: &format::View,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
     config: &crate::config::Config,
-) -> Result<()> {
+) -> Result<Vec<common::PriceReport>> {
     let extensions_results = extensions::package::dependencies_locks(
         &package_name,
         &package_version,
@@ -18,23 +23,42 @@ pub fn price(
     )?;
 
     let mut locks_found = false;
+    let mut price_reports = vec![];
 
     for (_extension, extension_result) in
         extensions::common::filter_results(&extensions, &extensions_results)?
     {
         locks_found |= extension_result.package_locks.has_locks();
-        if let Some(price_report) = common::get_report(&extension_result.package_locks, &config)? {
-            println!("Registry: {}", extension_result.registry_host_name);
-            println!("Total: {}", price_report.price);
-            format::print(&price_report, &format::Format::Table, true)?;
-            println!("");
+        let package_locks = extension_result
+            .package_locks
+            .filter_dependencies_kinds(&dependency_kinds);
+        if let Some(price_report) =
+            common::get_report(&package_locks, &billing_period, rate_provider, &config)?
+        {
+            if *format == format::Format::Table {
+                println!("Registry: {}", extension_result.registry_host_name);
+                common::print_total(&price_report);
+                format::print_report(&price_report, &package_locks, &format, &view)?;
+                println!("");
+            }
+            price_reports.push(price_report);
         }
     }
+    if *format != format::Format::Table {
+        super::project::print_combined(
+            &price_reports,
+            &billing_period,
+            &format,
+            &view,
+            rate_provider,
+            &config,
+        )?;
+    }
 
     if !locks_found {
-        println!("No OpenFare lock file found.")
+        eprintln!("No OpenFare lock file found.")
     }
-    Ok(())
+    Ok(price_reports)
 }
 
 pub fn query_extensions<'a>(
@@ -3,41 +3,126 @@ use anyhow::Result;
 use super::{common, format};
 use crate::extensions;
 
-/// Returns price information for a project and its dependencies.
+/// Prints price information for a project and its dependencies. Returns the reports.
 pub fn price(
     extensions: &Vec<Box<dyn openfare_lib::extension::Extension>>,
     extension_args: &Vec<String>,
+    dependency_kinds: &Vec<openfare_lib::package::DependencyKind>,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    format: &format::Format,
+    view: &format::View,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
     config: &crate::config::Config,
-) -> Result<()> {
+) -> Result<Vec<common::PriceReport>> {
     let working_directory = std::env::current_dir()?;
     log::debug!("Current working directory: {}", working_directory.display());
-    let extensions_results =
-        extensions::project::dependencies_locks(&working_directory, &extensions, &extension_args)?;
+    let project_reports = get_reports(
+        &working_directory,
+        &extensions,
+        &extension_args,
+        &dependency_kinds,
+        &billing_period,
+        rate_provider,
+        &config,
+    )?;
 
     let mut locks_found = false;
+    let mut price_reports = vec![];
 
-    for (_extension, extension_result) in
-        extensions::common::filter_results(&extensions, &extensions_results)?
-    {
-        locks_found |= extension_result.package_locks.has_locks();
-        if let Some(price_report) = common::get_report(&extension_result.package_locks, &config)? {
-            println!(
-                "Project: {path}",
-                path = extension_result.project_path.display()
-            );
-            println!("Total: {}", price_report.price);
-            format::print(&price_report, &format::Format::Table, true)?;
-            println!("");
+    for project_report in project_reports {
+        locks_found |= project_report.locks_found;
+        if let Some(price_report) = project_report.price_report {
+            if *format == format::Format::Table {
+                println!(
+                    "Project: {path}",
+                    path = project_report.project_path.display()
+                );
+                common::print_total(&price_report);
+                format::print_report(&price_report, &project_report.package_locks, &format, &view)?;
+                println!("");
+            }
+            price_reports.push(price_report);
         }
     }
+    if *format != format::Format::Table {
+        print_combined(
+            &price_reports,
+            &billing_period,
+            &format,
+            &view,
+            rate_provider,
+            &config,
+        )?;
+    }
 
     if !locks_found {
-        println!("No OpenFare lock files found.")
+        eprintln!("No OpenFare lock files found.")
     }
-    Ok(())
+    Ok(price_reports)
+}
+
+/// Prints the given reports combined into a single report. Machine-readable formats give a
+/// single document. The dependency tree view is not supported.
+pub fn print_combined(
+    price_reports: &Vec<common::PriceReport>,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    format: &format::Format,
+    view: &format::View,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+    config: &crate::config::Config,
+) -> Result<()> {
+    let price_report = common::combine(
+        &price_reports,
+        &config.core.preferred_currency,
+        &billing_period,
+        rate_provider,
+    )?;
+    format::print_report(&price_report, &Default::default(), &format, &view)
+}
+
+/// A project's dependencies locks and corresponding price report.
+pub struct ProjectPriceReport {
+    pub project_path: std::path::PathBuf,
+    /// True if any OpenFare lock was found before dependencies were filtered.
+    pub locks_found: bool,
+    pub package_locks: openfare_lib::package::PackageLocks,
+    pub price_report: Option<common::PriceReport>,
+}
+
+/// Generates price reports for the project in the given directory. One report per extension.
+pub fn get_reports(
+    working_directory: &std::path::PathBuf,
+    extensions: &Vec<Box<dyn openfare_lib::extension::Extension>>,
+    extension_args: &Vec<String>,
+    dependency_kinds: &Vec<openfare_lib::package::DependencyKind>,
+    billing_period: &Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+    config: &crate::config::Config,
+) -> Result<Vec<ProjectPriceReport>> {
+    let extensions_results =
+        extensions::project::dependencies_locks(&working_directory, &extensions, &extension_args)?;
+
+    let mut project_reports = vec![];
+    for (_extension, extension_result) in
+        extensions::common::filter_results(&extensions, &extensions_results)?
+    {
+        let package_locks = extension_result
+            .package_locks
+            .filter_dependencies_kinds(&dependency_kinds);
+        let price_report =
+            common::get_report(&package_locks, &billing_period, rate_provider, &config)?;
+        project_reports.push(ProjectPriceReport {
+            project_path: extension_result.project_path.clone(),
+            locks_found: extension_result.package_locks.has_locks(),
+            package_locks,
+            price_report,
+        });
+    }
+    Ok(project_reports)
 }
 
 pub fn query_extensions<'a>(
+    working_directory: &std::path::PathBuf,
     extensions: &'a Vec<Box<dyn openfare_lib::extension::Extension>>,
     extension_args: &Vec<String>,
 ) -> Result<
@@ -46,8 +131,7 @@ pub fn query_extensions<'a>(
         openfare_lib::extension::commands::project_dependencies_locks::ProjectDependenciesLocks,
     )>,
 > {
-    let working_directory = std::env::current_dir()?;
-    log::debug!("Current working directory: {}", working_directory.display());
+    log::debug!("Working directory: {}", working_directory.display());
     let extensions_results =
         extensions::project::dependencies_locks(&working_directory, &extensions, &extension_args)?;
     Ok(
@@ -0,0 +1,47 @@
+use super::common;
+use anyhow::Result;
+
+/// Returns an npmjs.com package.
+pub fn package(name: &str, version: &str) -> openfare_lib::package::Package {
+    openfare_lib::package::Package {
+        registry: "npmjs.com".to_string(),
+        name: name.to_string(),
+        version: version.to_string(),
+    }
+}
+
+/// Returns a package report with a single selected plan.
+pub fn package_report(
+    name: &str,
+    version: &str,
+    plan_type: openfare_lib::lock::plan::PlanType,
+    price_quantity: i64,
+) -> common::PackagePriceReport {
+    let compulsory_price_quantity = match plan_type {
+        openfare_lib::lock::plan::PlanType::Compulsory => Some(price_quantity.into()),
+        openfare_lib::lock::plan::PlanType::Voluntary => None,
+    };
+    common::PackagePriceReport {
+        package: package(name, version),
+        plan_ids: vec!["0".to_string()],
+        plan_type: Some(plan_type),
+        price_quantity: Some(price_quantity.into()),
+        compulsory_price_quantity,
+        notes: vec![],
+        payees: vec![],
+    }
+}
+
+/// Returns a price report without payee reports.
+pub fn price_report(
+    package_reports: Vec<common::PackagePriceReport>,
+    price: &str,
+) -> Result<common::PriceReport> {
+    Ok(common::PriceReport {
+        package_reports,
+        payee_reports: vec![],
+        price: price.parse()?,
+        billing_period: None,
+        one_off_price: None,
+    })
+}
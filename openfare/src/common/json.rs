@@ -48,9 +48,20 @@ where
         let mut json_value = serde_json::to_value(&subject)?;
 
         let mut target = &mut json_value;
-        for field in field_path.split('.') {
+        let fields = field_path.split('.').collect::<Vec<_>>();
+        let mut inserted = false;
+        for (index, field) in fields.iter().enumerate() {
+            // Unset optional fields may be omitted. Allow the final field to be added.
+            if index + 1 == fields.len() {
+                if let Some(object) = target.as_object_mut() {
+                    if !object.contains_key(*field) {
+                        object.insert(field.to_string(), serde_json::Value::Null);
+                        inserted = true;
+                    }
+                }
+            }
             target = target
-                .get_mut(field)
+                .get_mut(*field)
                 .ok_or(anyhow::format_err!("Failed to find field: {}", field))?;
         }
         let value = match serde_json::from_str(value) {
@@ -58,7 +69,58 @@ where
             Err(_) => serde_json::json!(value),
         };
         *target = value;
-        *subject = serde_json::from_value(json_value)?;
+        let updated: SubT = serde_json::from_value(json_value)?;
+
+        // An added field which is not part of the subject is dropped on deserialization.
+        if inserted {
+            let mut target = &serde_json::to_value(&updated)?;
+            for field in &fields {
+                target = target
+                    .get(*field)
+                    .ok_or(anyhow::format_err!("Failed to find field: {}", field))?;
+            }
+        }
+        *subject = updated;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+    struct Example {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        optional: Option<String>,
+        #[serde(default)]
+        map: std::collections::BTreeMap<String, u32>,
+    }
+
+    impl Subject<Example> for Example {
+        fn subject(&self) -> &Example {
+            &self
+        }
+        fn subject_mut(&mut self) -> &mut Example {
+            self
+        }
+    }
+
+    #[test]
+    fn test_set_adds_missing_fields() -> Result<()> {
+        let mut example = Example::default();
+        example.set("optional", "value")?;
+        assert_eq!(example.optional, Some("value".to_string()));
+        example.set("map.key", "1")?;
+        assert_eq!(example.map.get("key"), Some(&1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_unknown_field_fails() {
+        let mut example = Example::default();
+        assert!(example.set("nmae", "value").is_err());
+        assert!(example.set("name.inner", "value").is_err());
+    }
+}
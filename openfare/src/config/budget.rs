@@ -0,0 +1,29 @@
+/// Limits on compulsory fees.
+///
+/// Example: openfare config set budget.total "100 USD"
+#[derive(
+    Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct Budget {
+    /// Limit on the total compulsory fees of all packages.
+    #[serde(default)]
+    pub total: Option<openfare_lib::price::Price>,
+
+    /// Limits on the compulsory fees of individual packages. Keyed by package name.
+    #[serde(default)]
+    pub packages: std::collections::BTreeMap<String, openfare_lib::price::Price>,
+
+    /// Billing period to which recurring fees are normalized when checking limits.
+    #[serde(rename = "billing-period", default)]
+    pub billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+}
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string_pretty(&self).map_err(|_| std::fmt::Error::default())?
+        )
+    }
+}
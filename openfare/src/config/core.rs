@@ -1,9 +1,90 @@
-#[derive(
-    Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
-)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Core {
     #[serde(rename = "preferred-currency")]
     pub preferred_currency: openfare_lib::price::Currency,
+
+    /// Source of exchange rates.
+    #[serde(rename = "rate-provider", default)]
+    pub rate_provider: RateProvider,
+
+    /// Number of seconds for which cached exchange rates are reused.
+    #[serde(rename = "rates-cache-ttl", default = "default_rates_cache_ttl")]
+    pub rates_cache_ttl: u64,
+
+    /// How applicable plans are selected where a lock does not declare a policy.
+    #[serde(rename = "plan-selection", default)]
+    pub plan_selection: openfare_lib::lock::plan::selection_policy::SelectionPolicy,
+}
+
+impl std::default::Default for Core {
+    fn default() -> Self {
+        Self {
+            preferred_currency: Default::default(),
+            rate_provider: Default::default(),
+            rates_cache_ttl: default_rates_cache_ttl(),
+            plan_selection: Default::default(),
+        }
+    }
+}
+
+fn default_rates_cache_ttl() -> u64 {
+    600
+}
+
+/// Exchange rate provider selection.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type")]
+pub enum RateProvider {
+    /// Market rates from rate.sx.
+    #[serde(rename = "rate-sx")]
+    RateSx,
+
+    /// Rates table JSON file giving the price of one BTC in each currency.
+    /// Example: {"USD": "30000", "EUR": "27500.5"}
+    #[serde(rename = "static")]
+    Static { path: std::path::PathBuf },
+
+    /// Fixed price of one BTC used for every currency.
+    #[serde(rename = "fixed")]
+    Fixed {
+        #[serde(rename = "one-btc")]
+        one_btc: rust_decimal::Decimal,
+    },
+}
+
+impl std::default::Default for RateProvider {
+    fn default() -> Self {
+        Self::RateSx
+    }
+}
+
+impl RateProvider {
+    pub fn get(&self) -> anyhow::Result<Box<dyn openfare_lib::price::RateProvider>> {
+        Ok(match self {
+            Self::RateSx => Box::new(openfare_lib::price::RateSx),
+            Self::Static { path } => Box::new(openfare_lib::price::StaticRates::from_file(&path)?),
+            Self::Fixed { one_btc } => Box::new(openfare_lib::price::FixedRate(one_btc.clone())),
+        })
+    }
+
+    /// Identifies the provider and the data from which it gives rates. Static rates are
+    /// identified by the rates file path and contents hash.
+    pub fn source(&self) -> anyhow::Result<String> {
+        Ok(match self {
+            Self::RateSx => "rate-sx".to_string(),
+            Self::Static { path } => {
+                let contents = std::fs::read(&path).map_err(|error| {
+                    anyhow::format_err!("Failed to read rates file {}: {}", path.display(), error)
+                })?;
+                format!(
+                    "static:{path}:{hash}",
+                    path = path.display(),
+                    hash = blake3::hash(&contents).to_hex()
+                )
+            }
+            Self::Fixed { one_btc } => format!("fixed:{}", one_btc),
+        })
+    }
 }
 
 impl std::fmt::Display for Core {
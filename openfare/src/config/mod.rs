@@ -1,11 +1,13 @@
 use anyhow::Result;
 
+mod budget;
 mod core;
 mod extensions;
 mod paths;
 mod profile;
 pub mod services;
 
+pub use budget::Budget;
 pub use paths::Paths;
 
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
@@ -14,6 +16,8 @@ pub struct Config {
     pub services: services::Services,
     pub profile: profile::Profile,
     pub extensions: extensions::Extensions,
+    #[serde(default)]
+    pub budget: budget::Budget,
 }
 
 impl crate::common::json::Subject<Config> for Config {
@@ -6,6 +6,8 @@ pub struct Paths {
     pub root_directory: std::path::PathBuf,
     pub config_file: std::path::PathBuf,
     pub profile_file: std::path::PathBuf,
+    pub schedule_file: std::path::PathBuf,
+    pub rates_cache_file: std::path::PathBuf,
     pub extensions_directory: std::path::PathBuf,
 }
 
@@ -19,6 +21,8 @@ impl Paths {
             root_directory: root_directory.into(),
             config_file: root_directory.join("config.json"),
             profile_file: root_directory.join("profile.json"),
+            schedule_file: root_directory.join("schedule.json"),
+            rates_cache_file: root_directory.join("rates.json"),
             extensions_directory: root_directory.join("extensions"),
         })
     }
@@ -7,6 +7,8 @@ mod config;
 mod extensions;
 mod handles;
 mod payments;
+mod rates;
+mod schedule;
 mod services;
 mod setup;
 
@@ -4,19 +4,26 @@ pub fn donation_splits(
     donation: &openfare_lib::price::Price,
     items: &Vec<openfare_lib::api::services::basket::Item>,
     is_payee_applicable: fn(&openfare_lib::lock::payee::Payee) -> Result<bool>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
 ) -> Result<Vec<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>> {
     match &donation.currency {
         openfare_lib::price::Currency::SATS => println!("Donation: {}", donation),
         _ => println!(
             "Donation: {} ({sats})",
             donation,
-            sats = donation.to_sats()?
+            sats = donation.to_sats(rate_provider)?
         ),
     }
-    let donation = donation.to_sats()?;
+    // Split in msat: the smallest unit payable over the lightning network.
+    let donation = donation.to_msat(rate_provider)?;
 
     // Filter for package which has a volunteer plan and at least one applicable payee.
     let items = filter_voluntary(&items, is_payee_applicable);
+    if items.is_empty() {
+        return Err(anyhow::format_err!(
+            "Failed to find a package with a voluntary plan and applicable payee to donate to."
+        ));
+    }
 
     // Round down to avoid overflowing specified donation.
     let package_donation_quantity = (donation.quantity / rust_decimal::Decimal::from(items.len()))
@@ -83,10 +90,93 @@ pub fn donation_splits(
         }
     }
 
-    check_payee_donations(&donation, &payee_donations);
+    check_payee_donations(&donation, &payee_donations)?;
     Ok(payee_donations)
 }
 
+/// Compulsory fee, in msat, of each basket item package.
+pub type CompulsoryFees =
+    std::collections::BTreeMap<openfare_lib::package::Package, openfare_lib::price::Price>;
+
+/// Returns the compulsory fee of a basket item in msat. Each compulsory plan price is converted
+/// from its own currency.
+pub fn compulsory_fee(
+    item: &openfare_lib::api::services::basket::Item,
+    parameters: &openfare_lib::lock::plan::conditions::Parameters,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
+) -> Result<openfare_lib::price::Price> {
+    let mut fee = openfare_lib::price::Price {
+        quantity: openfare_lib::price::Quantity::from(0),
+        currency: openfare_lib::price::Currency::MSAT,
+    };
+    for plan in item.plans.values() {
+        if plan.r#type != openfare_lib::lock::plan::PlanType::Compulsory {
+            continue;
+        }
+        if let Some(price) = plan.total_price(&parameters)? {
+            fee = (fee + price.to_msat(rate_provider)?)?;
+        }
+    }
+    Ok(fee)
+}
+
+/// Splits the compulsory fees of the given basket items between payees according to shares.
+///
+/// Portions owed to payees which are not applicable to the payment service are skipped. Returns
+/// the splits and the packages whose fees could not be split in full.
+pub fn compulsory_splits(
+    items: &Vec<openfare_lib::api::services::basket::Item>,
+    fees: &CompulsoryFees,
+    is_payee_applicable: fn(&openfare_lib::lock::payee::Payee) -> Result<bool>,
+) -> (
+    Vec<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>,
+    Vec<openfare_lib::package::Package>,
+) {
+    let mut payee_fees =
+        Vec::<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>::new();
+    let mut unpaid_packages = vec![];
+    for item in items {
+        let fee = match fees.get(&item.package) {
+            Some(fee) if !fee.quantity.is_zero() => fee,
+            _ => continue,
+        };
+        println!(
+            "Compulsory fee for {name} ({version}): {fee}",
+            name = item.package.name,
+            version = item.package.version,
+            fee = fee
+        );
+
+        let fractions = openfare_lib::lock::payee::fractions(&item.payees, &item.shares);
+        if fractions.is_empty() {
+            unpaid_packages.push(item.package.clone());
+        }
+        for (payee, fraction) in fractions {
+            // Round down to avoid overflowing the package fee.
+            let payee_fee = openfare_lib::price::Price {
+                quantity: (fee.quantity * fraction).round_dp_with_strategy(
+                    fee.currency.decimal_points(),
+                    rust_decimal::prelude::RoundingStrategy::ToZero,
+                ),
+                currency: fee.currency.clone(),
+            };
+            if is_payee_applicable(&payee).unwrap_or(false) {
+                payee_fees.push((payee, payee_fee));
+            } else {
+                println!(
+                    "Skipping {payee_fee} owed to payee without supported payment method: {unique_id}",
+                    payee_fee = payee_fee,
+                    unique_id = payee.profile.unique_id
+                );
+                if !unpaid_packages.contains(&item.package) {
+                    unpaid_packages.push(item.package.clone());
+                }
+            }
+        }
+    }
+    (payee_fees, unpaid_packages)
+}
+
 /// Filter for items which have at least one voluntary payment plan and corresponding applicable payee.
 fn filter_voluntary(
     items: &Vec<openfare_lib::api::services::basket::Item>,
@@ -135,10 +225,216 @@ fn filter_for_applicable_shares(
 fn check_payee_donations(
     total_donation: &openfare_lib::price::Price,
     payee_donations: &Vec<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>,
-) {
+) -> Result<()> {
     let total_payee_donations: openfare_lib::price::Quantity = payee_donations
         .iter()
         .map(|(_, price)| price.quantity)
         .sum();
-    assert!(total_donation.quantity >= total_payee_donations);
+    if total_payee_donations > total_donation.quantity {
+        return Err(anyhow::format_err!(
+            "Payee donations total {total_payee_donations} exceeds donation: {total_donation}",
+            total_payee_donations = total_payee_donations,
+            total_donation = total_donation
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn payee(url: Option<&str>) -> openfare_lib::lock::payee::Payee {
+        openfare_lib::lock::payee::Payee {
+            url: url.map(|url| url.to_string()),
+            profile: openfare_lib::profile::Profile {
+                unique_id: uuid::Uuid::new_v4(),
+                payment_methods: Default::default(),
+                price_cap: None,
+            },
+        }
+    }
+
+    /// Basket item with a single plan. Payees a and b have an applicable payment method, c does
+    /// not. Shares are 1:2:1.
+    fn item(
+        name: &str,
+        plan_type: openfare_lib::lock::plan::PlanType,
+        price: Option<&str>,
+    ) -> Result<openfare_lib::api::services::basket::Item> {
+        let price = price
+            .map(|price| openfare_lib::price::Price::from_str(price))
+            .transpose()?;
+        let mut plans = std::collections::BTreeMap::new();
+        plans.insert(
+            "0".to_string(),
+            openfare_lib::lock::plan::Plan {
+                r#type: plan_type,
+                conditions: Default::default(),
+                price: price.clone(),
+                unit: None,
+                billing_period: None,
+                priority: None,
+            },
+        );
+        let mut payees = openfare_lib::lock::payee::Payees::new();
+        payees.insert("a".to_string(), payee(Some("https://a.example")));
+        payees.insert("b".to_string(), payee(Some("https://b.example")));
+        payees.insert("c".to_string(), payee(None));
+        let mut shares = openfare_lib::lock::shares::Shares::new();
+        shares.insert("a".to_string(), 1);
+        shares.insert("b".to_string(), 2);
+        shares.insert("c".to_string(), 1);
+        Ok(openfare_lib::api::services::basket::Item {
+            package: openfare_lib::package::Package {
+                registry: "npmjs.com".to_string(),
+                name: name.to_string(),
+                version: "1.0.0".to_string(),
+            },
+            extension_name: "js".to_string(),
+            plans,
+            total_price: price.unwrap_or_default(),
+            payees,
+            shares: Some(shares),
+        })
+    }
+
+    fn is_payee_applicable(payee: &openfare_lib::lock::payee::Payee) -> Result<bool> {
+        Ok(payee.url.is_some())
+    }
+
+    #[test]
+    fn test_compulsory_fee() -> Result<()> {
+        let compulsory = openfare_lib::lock::plan::PlanType::Compulsory;
+        let rate_provider = openfare_lib::price::FixedRate(30000.into());
+        let parameters = Default::default();
+
+        // Sats prices are not converted via the preferred currency.
+        let fee = compulsory_fee(
+            &item("a", compulsory.clone(), Some("150 sats"))?,
+            &parameters,
+            &rate_provider,
+        )?;
+        assert_eq!(fee, openfare_lib::price::Price::from_str("150000 msat")?);
+
+        let fee = compulsory_fee(
+            &item("a", compulsory.clone(), Some("10 USD"))?,
+            &parameters,
+            &rate_provider,
+        )?;
+        assert_eq!(fee, openfare_lib::price::Price::from_str("33333334 msat")?);
+
+        let fee = compulsory_fee(
+            &item("b", openfare_lib::lock::plan::PlanType::Voluntary, None)?,
+            &parameters,
+            &rate_provider,
+        )?;
+        assert!(fee.quantity.is_zero());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compulsory_splits() -> Result<()> {
+        let items = vec![
+            item(
+                "a",
+                openfare_lib::lock::plan::PlanType::Compulsory,
+                Some("150 sats"),
+            )?,
+            item("b", openfare_lib::lock::plan::PlanType::Voluntary, None)?,
+        ];
+        let mut fees = CompulsoryFees::new();
+        fees.insert(
+            items[0].package.clone(),
+            openfare_lib::price::Price::from_str("150000 msat")?,
+        );
+        let (splits, unpaid_packages) = compulsory_splits(&items, &fees, is_payee_applicable);
+
+        // Payee c has no applicable payment method.
+        let splits = splits
+            .iter()
+            .map(|(payee, price)| (payee.url.clone().unwrap_or_default(), price.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            splits,
+            vec![
+                (
+                    "https://a.example".to_string(),
+                    openfare_lib::price::Price::from_str("37500 msat")?
+                ),
+                (
+                    "https://b.example".to_string(),
+                    openfare_lib::price::Price::from_str("75000 msat")?
+                ),
+            ]
+        );
+        assert_eq!(unpaid_packages, vec![items[0].package.clone()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_donation_splits() -> Result<()> {
+        let items = vec![
+            item(
+                "a",
+                openfare_lib::lock::plan::PlanType::Compulsory,
+                Some("150 sats"),
+            )?,
+            item("b", openfare_lib::lock::plan::PlanType::Voluntary, None)?,
+        ];
+        let rate_provider = openfare_lib::price::FixedRate(30000.into());
+        let donation = openfare_lib::price::Price::from_str("100 sats")?;
+        let splits = donation_splits(&donation, &items, is_payee_applicable, &rate_provider)?;
+
+        // Only package b has a voluntary plan. Payee c has no applicable payment method.
+        let splits = splits
+            .iter()
+            .map(|(payee, price)| (payee.url.clone().unwrap_or_default(), price.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            splits,
+            vec![
+                (
+                    "https://a.example".to_string(),
+                    openfare_lib::price::Price::from_str("33333 msat")?
+                ),
+                (
+                    "https://b.example".to_string(),
+                    openfare_lib::price::Price::from_str("66666 msat")?
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_donation_splits_without_voluntary_plans_is_error() -> Result<()> {
+        let items = vec![item(
+            "a",
+            openfare_lib::lock::plan::PlanType::Compulsory,
+            Some("150 sats"),
+        )?];
+        let rate_provider = openfare_lib::price::FixedRate(30000.into());
+        let donation = openfare_lib::price::Price::from_str("100 sats")?;
+        assert!(donation_splits(&donation, &items, is_payee_applicable, &rate_provider).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_payee_donations_exceeding_total_is_error() -> Result<()> {
+        let donation = openfare_lib::price::Price::from_str("100 msat")?;
+        let payee_donations = vec![
+            (
+                payee(None),
+                openfare_lib::price::Price::from_str("60 msat")?,
+            ),
+            (
+                payee(None),
+                openfare_lib::price::Price::from_str("50 msat")?,
+            ),
+        ];
+        assert!(check_payee_donations(&donation, &payee_donations).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,132 @@
+use crate::common::fs::FileStore;
+use anyhow::Result;
+
+/// Exchange rates cached on disk between command runs.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Cache {
+    pub rates: Option<openfare_lib::price::Rates>,
+
+    /// Rate provider and source from which the cached rates were given.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl crate::common::fs::FilePath for Cache {
+    fn file_path() -> Result<std::path::PathBuf> {
+        let paths = crate::config::Paths::new()?;
+        Ok(paths.rates_cache_file)
+    }
+}
+
+/// Returns an exchange rates snapshot for a single command run.
+///
+/// Cached rates are reused if younger than the configured TTL and given by the configured
+/// rate provider and source.
+pub fn snapshot(config: &crate::config::Config) -> Result<openfare_lib::price::Snapshot> {
+    let provider = config.core.rate_provider.get()?;
+    let source = config.core.rate_provider.source()?;
+    let ttl = chrono::Duration::seconds(config.core.rates_cache_ttl as i64);
+
+    let rates = match Cache::load() {
+        Ok(cache) => get_cached_rates(&cache, &source, &ttl, &chrono::Utc::now()),
+        Err(error) => {
+            log::debug!("Failed to load exchange rates cache: {}", error);
+            None
+        }
+    }
+    .unwrap_or_else(openfare_lib::price::Rates::new);
+    Ok(openfare_lib::price::Snapshot::new(rates, provider))
+}
+
+/// Returns the cached rates if given by the given rate provider source and younger than the
+/// given TTL.
+fn get_cached_rates(
+    cache: &Cache,
+    source: &str,
+    ttl: &chrono::Duration,
+    now: &chrono::DateTime<chrono::Utc>,
+) -> Option<openfare_lib::price::Rates> {
+    if cache.source.as_deref() != Some(source) {
+        log::debug!("Discarding exchange rates cache from different rate provider source.");
+        return None;
+    }
+    match &cache.rates {
+        Some(rates) if *now - rates.time < *ttl => {
+            log::debug!("Using cached exchange rates from: {}", rates.time);
+            Some(rates.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Stores the rates of the given snapshot in the on disk cache.
+pub fn store(
+    snapshot: &openfare_lib::price::Snapshot,
+    config: &crate::config::Config,
+) -> Result<()> {
+    let mut cache = Cache {
+        rates: Some(snapshot.rates()),
+        source: Some(config.core.rate_provider.source()?),
+    };
+    cache.dump()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(source: &str) -> Cache {
+        let mut rates = openfare_lib::price::Rates::new();
+        rates
+            .rates
+            .insert(openfare_lib::price::Currency::USD, 30000.into());
+        Cache {
+            rates: Some(rates),
+            source: Some(source.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_cached_rates_expire_after_ttl() {
+        let cache = cache("rate-sx");
+        let time = cache.rates.as_ref().unwrap().time;
+        let ttl = chrono::Duration::seconds(60);
+
+        let rates = get_cached_rates(
+            &cache,
+            "rate-sx",
+            &ttl,
+            &(time + chrono::Duration::seconds(59)),
+        );
+        assert_eq!(rates, cache.rates);
+        let rates = get_cached_rates(
+            &cache,
+            "rate-sx",
+            &ttl,
+            &(time + chrono::Duration::seconds(60)),
+        );
+        assert_eq!(rates, None);
+    }
+
+    #[test]
+    fn test_cached_rates_keyed_by_source() {
+        let cache = cache("fixed:30000");
+        let time = cache.rates.as_ref().unwrap().time;
+        let ttl = chrono::Duration::seconds(60);
+
+        assert!(get_cached_rates(&cache, "fixed:30000", &ttl, &time).is_some());
+        assert_eq!(get_cached_rates(&cache, "fixed:40000", &ttl, &time), None);
+        assert_eq!(get_cached_rates(&cache, "rate-sx", &ttl, &time), None);
+
+        // Caches written before sources were recorded are discarded.
+        let legacy_cache = Cache {
+            source: None,
+            ..cache
+        };
+        assert_eq!(
+            get_cached_rates(&legacy_cache, "fixed:30000", &ttl, &time),
+            None
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::Result;
+
+/// Record of paid plans and when their next payments fall due.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Schedule {
+    pub payments: Vec<Payment>,
+
+    /// Voluntary donations made.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub donations: Vec<Donation>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Payment {
+    pub package: openfare_lib::package::Package,
+    #[serde(rename = "plan-id")]
+    pub plan_id: openfare_lib::lock::plan::Id,
+    #[serde(rename = "billing-period")]
+    pub billing_period: openfare_lib::lock::plan::billing_period::BillingPeriod,
+    pub paid: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "next-due")]
+    pub next_due: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Exchange rates used for the payment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rates: Option<openfare_lib::price::Rates>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Donation {
+    pub donation: openfare_lib::price::Price,
+    pub paid: chrono::DateTime<chrono::Utc>,
+
+    /// Exchange rates used for the payment.
+    pub rates: openfare_lib::price::Rates,
+}
+
+impl crate::common::fs::FilePath for Schedule {
+    fn file_path() -> Result<std::path::PathBuf> {
+        let paths = crate::config::Paths::new()?;
+        Ok(paths.schedule_file)
+    }
+}
+
+impl Schedule {
+    /// Records payment of the compulsory plans of the given basket items using the given
+    /// exchange rates.
+    ///
+    /// Replaces existing records for the same package (any version) and plan.
+    /// Returns the new records.
+    pub fn record(
+        &mut self,
+        items: &Vec<openfare_lib::api::services::basket::Item>,
+        paid: &chrono::DateTime<chrono::Utc>,
+        rates: &openfare_lib::price::Rates,
+    ) -> Vec<Payment> {
+        let mut payments = vec![];
+        for item in items {
+            for (plan_id, plan) in &item.plans {
+                if plan.r#type != openfare_lib::lock::plan::PlanType::Compulsory
+                    || plan.price.is_none()
+                {
+                    continue;
+                }
+                let billing_period = plan.billing_period.clone().unwrap_or_default();
+                payments.push(Payment {
+                    package: item.package.clone(),
+                    plan_id: plan_id.clone(),
+                    next_due: billing_period.next_due(&paid),
+                    billing_period,
+                    paid: paid.clone(),
+                    rates: Some(rates.clone()),
+                });
+            }
+        }
+
+        self.payments.retain(|existing| {
+            !payments.iter().any(|payment| {
+                payment.package.registry == existing.package.registry
+                    && payment.package.name == existing.package.name
+                    && payment.plan_id == existing.plan_id
+            })
+        });
+        self.payments.extend(payments.iter().cloned());
+        payments
+    }
+
+    /// Records a voluntary donation using the given exchange rates.
+    pub fn record_donation(
+        &mut self,
+        donation: &openfare_lib::price::Price,
+        paid: &chrono::DateTime<chrono::Utc>,
+        rates: &openfare_lib::price::Rates,
+    ) {
+        self.donations.push(Donation {
+            donation: donation.clone(),
+            paid: paid.clone(),
+            rates: rates.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn plan(
+        plan_type: openfare_lib::lock::plan::PlanType,
+        price: Option<&str>,
+        billing_period: Option<openfare_lib::lock::plan::billing_period::BillingPeriod>,
+    ) -> Result<openfare_lib::lock::plan::Plan> {
+        Ok(openfare_lib::lock::plan::Plan {
+            r#type: plan_type,
+            conditions: Default::default(),
+            price: price
+                .map(|price| openfare_lib::price::Price::from_str(price))
+                .transpose()?,
+            unit: None,
+            billing_period,
+            priority: None,
+        })
+    }
+
+    fn item(
+        version: &str,
+        plans: Vec<openfare_lib::lock::plan::Plan>,
+    ) -> openfare_lib::api::services::basket::Item {
+        openfare_lib::api::services::basket::Item {
+            package: openfare_lib::package::Package {
+                registry: "npmjs.com".to_string(),
+                name: "a".to_string(),
+                version: version.to_string(),
+            },
+            extension_name: "js".to_string(),
+            plans: plans
+                .into_iter()
+                .enumerate()
+                .map(|(index, plan)| (index.to_string(), plan))
+                .collect(),
+            total_price: Default::default(),
+            payees: Default::default(),
+            shares: None,
+        }
+    }
+
+    fn date(value: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+        Ok(chrono::DateTime::parse_from_rfc3339(value)?.with_timezone(&chrono::Utc))
+    }
+
+    #[test]
+    fn test_record_compulsory_priced_plans() -> Result<()> {
+        let monthly = openfare_lib::lock::plan::billing_period::BillingPeriod::Monthly;
+        let items = vec![item(
+            "1.0.0",
+            vec![
+                plan(
+                    openfare_lib::lock::plan::PlanType::Compulsory,
+                    Some("10 USD"),
+                    Some(monthly.clone()),
+                )?,
+                plan(openfare_lib::lock::plan::PlanType::Compulsory, None, None)?,
+                plan(
+                    openfare_lib::lock::plan::PlanType::Voluntary,
+                    Some("5 USD"),
+                    None,
+                )?,
+                plan(
+                    openfare_lib::lock::plan::PlanType::Compulsory,
+                    Some("100 USD"),
+                    None,
+                )?,
+            ],
+        )];
+        let paid = date("2022-01-31T12:00:00Z")?;
+        let rates = openfare_lib::price::Rates::new();
+
+        let mut schedule = Schedule::default();
+        let payments = schedule.record(&items, &paid, &rates);
+        assert_eq!(payments, schedule.payments);
+        assert_eq!(
+            payments
+                .iter()
+                .map(|payment| (payment.plan_id.as_str(), payment.next_due))
+                .collect::<Vec<_>>(),
+            vec![("0", Some(date("2022-02-28T12:00:00Z")?)), ("3", None)]
+        );
+        assert_eq!(payments[0].billing_period, monthly);
+        assert_eq!(payments[0].rates, Some(rates));
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_replaces_previous_package_version_payments() -> Result<()> {
+        let compulsory_plan = || {
+            plan(
+                openfare_lib::lock::plan::PlanType::Compulsory,
+                Some("10 USD"),
+                Some(openfare_lib::lock::plan::billing_period::BillingPeriod::Yearly),
+            )
+        };
+        let rates = openfare_lib::price::Rates::new();
+
+        let mut schedule = Schedule::default();
+        schedule.record(
+            &vec![item("1.0.0", vec![compulsory_plan()?])],
+            &date("2022-01-01T00:00:00Z")?,
+            &rates,
+        );
+        schedule.record(
+            &vec![item("2.0.0", vec![compulsory_plan()?])],
+            &date("2023-01-01T00:00:00Z")?,
+            &rates,
+        );
+
+        assert_eq!(schedule.payments.len(), 1);
+        assert_eq!(schedule.payments[0].package.version, "2.0.0");
+        assert_eq!(
+            schedule.payments[0].next_due,
+            Some(date("2024-01-01T00:00:00Z")?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_donation() -> Result<()> {
+        let donation = openfare_lib::price::Price::from_str("200 sats")?;
+        let paid = date("2022-01-31T12:00:00Z")?;
+        let rates = openfare_lib::price::Rates::new();
+
+        let mut schedule = Schedule::default();
+        schedule.record_donation(&donation, &paid, &rates);
+        assert_eq!(
+            schedule.donations,
+            vec![Donation {
+                donation,
+                paid,
+                rates
+            }]
+        );
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use serde::de::Deserialize;
 
 static BASE_URL: &str = "https://api.lnpay.co/v1/";
 static DEFAULT_WALLET_NAME: &str = "openfare";
+static MSAT_PER_SAT: i64 = 1000;
 
 pub type Invoice = String;
 
@@ -229,26 +230,72 @@ pub struct Wallet {
     default_lnurlpay_id: Option<String>,
 }
 
+/// Pays compulsory fees and donations. Returns true if payment was made.
 pub fn pay(
     donation_splits: &Option<Vec<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>>,
-    _items: &Vec<openfare_lib::api::services::portal::basket::Item>,
+    compulsory_splits: &Vec<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>,
     config: &crate::config::Config,
-) -> Result<()> {
+) -> Result<bool> {
     let lnpay_config = config
         .services
         .lnpay.clone().ok_or(anyhow::format_err!("Failed to find LNPAY config under services. Add LNPAY service: openfare service add lnpay --api-key=<key>"))?;
-    if let Some(donation_splits) = donation_splits {
-        pay_splits(&donation_splits, &lnpay_config)?;
+
+    let donation_splits = donation_splits.clone().unwrap_or_default();
+    let splits = compulsory_splits
+        .iter()
+        .chain(donation_splits.iter())
+        .filter(|(_, price)| !price.quantity.is_zero())
+        .cloned()
+        .collect::<Vec<_>>();
+    if splits.is_empty() {
+        println!("No applicable payments found.");
+        return Ok(false);
     }
-    // TODO: Handle applicable compulsory payments.
-    Ok(())
+
+    let compulsory_msat = total_msat(&compulsory_splits);
+    let voluntary_msat = total_msat(&donation_splits);
+    println!(
+        "Compulsory fees: {compulsory} SATS",
+        compulsory = compulsory_msat / rust_decimal::Decimal::from(MSAT_PER_SAT)
+    );
+    println!(
+        "Voluntary donations: {voluntary} SATS",
+        voluntary = voluntary_msat / rust_decimal::Decimal::from(MSAT_PER_SAT)
+    );
+    println!(
+        "Total: {total} SATS ({count_payees} payments)",
+        total = (compulsory_msat + voluntary_msat) / rust_decimal::Decimal::from(MSAT_PER_SAT),
+        count_payees = splits.len()
+    );
+    if !dialoguer::Confirm::new()
+        .with_prompt("Proceed with payment?")
+        .interact()?
+    {
+        return Ok(false);
+    }
+    pay_splits(&splits, &lnpay_config)
+}
+
+fn total_msat(
+    splits: &Vec<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>,
+) -> rust_decimal::Decimal {
+    splits.iter().map(|(_, price)| price.quantity).sum()
 }
 
 fn pay_splits(
     splits: &Vec<(openfare_lib::lock::payee::Payee, openfare_lib::price::Price)>,
     lnpay_config: &crate::config::services::lnpay::LnPay,
-) -> Result<()> {
-    let total_payment: rust_decimal::Decimal = splits.iter().map(|(_, price)| price.quantity).sum();
+) -> Result<bool> {
+    if let Some((_, price)) = splits
+        .iter()
+        .find(|(_, price)| price.currency != openfare_lib::price::Currency::MSAT)
+    {
+        return Err(anyhow::format_err!(
+            "Code error: split payment not given in MSAT: {}",
+            price
+        ));
+    }
+    let total_payment_msat = total_msat(&splits);
     let client = Client::new(&lnpay_config.api_key);
 
     loop {
@@ -261,11 +308,15 @@ fn pay_splits(
                 "Adding lightning network fee buffer: {}",
                 lightning_network_fee_buffer
             );
-            let remainder = (total_payment + lightning_network_fee_buffer) - balance;
-            if remainder > rust_decimal::Decimal::from(0 as i64) {
-                let retry = handle_insufficient_balance(&remainder, &balance, &wallet, &client)?;
+            let remainder_msat = (total_payment_msat
+                + (lightning_network_fee_buffer - balance)
+                    * rust_decimal::Decimal::from(MSAT_PER_SAT))
+            .ceil();
+            if remainder_msat > rust_decimal::Decimal::from(0 as i64) {
+                let retry =
+                    handle_insufficient_balance(&remainder_msat, &balance, &wallet, &client)?;
                 if !retry {
-                    break;
+                    return Ok(false);
                 }
             } else {
                 println!("Found sufficient funds in wallet: {:?}", wallet);
@@ -274,31 +325,30 @@ fn pay_splits(
                     let lnurl = get_lnurl(&payee.profile)?.ok_or(anyhow::format_err!(
                         "Code error: Failed to find LNURL for split payment."
                     ))?;
-                    let amount = amount.quantity.to_usize().ok_or(anyhow::format_err!(
+                    let amount_msat = amount.quantity.to_usize().ok_or(anyhow::format_err!(
                         "Failed to parse amount quantity as usize."
                     ))?;
-                    let amount_msat = amount * 1000;
                     // TODO: Add LNURL comment giving origin.
                     client.pay_lnurl(&lnurl, amount_msat, &wallet, "")?;
                 }
-                break;
+                return Ok(true);
             }
         }
     }
-    Ok(())
 }
 
 fn handle_insufficient_balance(
-    remainder: &rust_decimal::Decimal,
+    remainder_msat: &rust_decimal::Decimal,
     balance: &rust_decimal::Decimal,
     wallet: &Wallet,
     client: &Client,
 ) -> Result<bool> {
     let lnurl = client.get_lnurl(&wallet)?;
-    let remainder = remainder.to_usize().ok_or(anyhow::format_err!(
-        "Code error: remainder sats cant be represented as usize."
+    let invoice_msat = remainder_msat.to_usize().ok_or(anyhow::format_err!(
+        "Code error: remainder msat cant be represented as usize."
     ))?;
-    let invoice = client.invoice_from_lnurl(remainder * 1000, &lnurl)?;
+    let invoice = client.invoice_from_lnurl(invoice_msat, &lnurl)?;
+    let remainder = remainder_msat / rust_decimal::Decimal::from(MSAT_PER_SAT);
 
     println!(
         "Wallet '{DEFAULT_WALLET_NAME}' does not contain enough SATS. Current balance: {balance}.",
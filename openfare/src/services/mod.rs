@@ -24,33 +24,54 @@ impl std::str::FromStr for Service {
     }
 }
 
+/// Pays for the given basket items using the given service.
+///
+/// Returns the items paid in full if payment was confirmed.
 pub fn pay(
     donation: &Option<openfare_lib::price::Price>,
     items: &Vec<openfare_lib::api::services::basket::Item>,
+    fees: &crate::payments::CompulsoryFees,
     service: &Option<Service>,
+    rate_provider: &dyn openfare_lib::price::RateProvider,
     config: &crate::config::Config,
-) -> Result<()> {
+) -> Result<Option<Vec<openfare_lib::api::services::basket::Item>>> {
     println!("Found {} packages with OpenFare support.", items.len());
     if items.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
     let service = service.clone().unwrap_or(config.services.default.clone());
-    match service {
-        Service::Portal => portal::pay(&items, &config)?,
+    Ok(match service {
+        Service::Portal => {
+            // Payment is completed via the checkout URL and can not be confirmed here.
+            portal::pay(&items, &config)?;
+            None
+        }
         Service::LnPay => {
             let donation_splits = if let Some(donation) = donation {
                 Some(crate::payments::donation_splits(
                     &donation,
                     &items,
                     lnpay::is_payee_applicable,
+                    rate_provider,
                 )?)
             } else {
                 None
             };
-            lnpay::pay(&donation_splits, &items, &config)?
+            let (compulsory_splits, unpaid_packages) =
+                crate::payments::compulsory_splits(&items, &fees, lnpay::is_payee_applicable);
+            if lnpay::pay(&donation_splits, &compulsory_splits, &config)? {
+                Some(
+                    items
+                        .iter()
+                        .filter(|item| !unpaid_packages.contains(&item.package))
+                        .cloned()
+                        .collect(),
+                )
+            } else {
+                None
+            }
         }
-    }
-    Ok(())
+    })
 }
 
 pub fn lnurl_receive_address(